@@ -2,6 +2,8 @@ pub mod explorer;
 pub mod text_field;
 pub mod util;
 pub mod text_input;
+pub mod number_input;
+pub mod masked_input;
 mod core;
 mod row_view;
 
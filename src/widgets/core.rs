@@ -1,3 +1,11 @@
+#[cfg(feature = "backend-crossterm")]
+mod crossterm;
+
+use std::any::Any;
+use std::marker::PhantomData;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::{StatefulWidgetRef, WidgetRef};
 
 pub trait RemyWidget: WidgetRef {
@@ -26,3 +34,134 @@ pub trait RemyWidgetState: Sized {
 pub trait StatefulRemyWidget: StatefulWidgetRef<State=Self::Input> {
     type Input: RemyWidgetState;
 }
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Cursor reporting
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CursorShape {
+    #[default]
+    Bar,
+    Block,
+    Underline
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CursorPosition {
+    pub x: u16,
+    pub y: u16,
+    pub shape: CursorShape
+}
+
+impl From<CursorPosition> for Position {
+    fn from(value: CursorPosition) -> Self {
+        Position::new(value.x, value.y)
+    }
+}
+
+/// Implemented by editable widgets to report where the terminal cursor
+/// should be placed when the widget is rendered in the given area.
+pub trait CursorProvider: StatefulRemyWidget {
+    fn cursor(&self, area: Rect, state: &mut Self::Input) -> Option<CursorPosition>;
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Type-erased widgets
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Type-erased `EventOutput` of a widget wrapped in a `DynRemyWidget`.
+/// The concrete output can be recovered using `downcast`.
+pub struct DynOutput(Box<dyn Any>);
+
+impl DynOutput {
+    pub fn new<O: Any>(output: O) -> Self {
+        Self(Box::new(output))
+    }
+
+    pub fn is<O: Any>(&self) -> bool {
+        self.0.is::<O>()
+    }
+
+    pub fn downcast<O: Any>(self) -> Result<O, Self> {
+        self.0.downcast::<O>()
+            .map(|boxed| *boxed)
+            .map_err(Self)
+    }
+
+    pub fn downcast_ref<O: Any>(&self) -> Option<&O> {
+        self.0.downcast_ref::<O>()
+    }
+}
+
+impl std::fmt::Debug for DynOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DynOutput").finish_non_exhaustive()
+    }
+}
+
+
+/// Object-safe interface to a widget, its state, and its input converter,
+/// allowing heterogeneous widgets to be stored as `Box<dyn DynRemyWidget>`.
+pub trait DynRemyWidget {
+    fn handle(&mut self, event: crate::events::Event) -> DynOutput;
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer);
+}
+
+
+pub struct RemyWidgetBundle<W, C>
+where
+    W: StatefulRemyWidget
+{
+    widget: W,
+    state: W::Input,
+    _converter: PhantomData<C>
+}
+
+impl<W, C> RemyWidgetBundle<W, C>
+where
+    W: StatefulRemyWidget,
+    C: RemyWidgetCommandConverter<W::Input, Event=<W::Input as RemyWidgetState>::Command>
+{
+    pub fn new(widget: W, state: W::Input) -> Self {
+        Self {
+            widget,
+            state,
+            _converter: PhantomData
+        }
+    }
+
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    pub fn state(&self) -> &W::Input {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut W::Input {
+        &mut self.state
+    }
+
+    pub fn into_inner(self) -> (W, W::Input) {
+        (self.widget, self.state)
+    }
+}
+
+impl<W, C> DynRemyWidget for RemyWidgetBundle<W, C>
+where
+    W: StatefulRemyWidget,
+    C: RemyWidgetCommandConverter<W::Input, Event=<W::Input as RemyWidgetState>::Command>,
+    <W::Input as RemyWidgetState>::EventOutput: 'static
+{
+    fn handle(&mut self, event: crate::events::Event) -> DynOutput {
+        DynOutput::new(self.state.handle_events::<C>(event))
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.widget.render_ref(area, buf, &mut self.state);
+    }
+}
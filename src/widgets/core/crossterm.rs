@@ -0,0 +1,14 @@
+use ratatui::crossterm::cursor::SetCursorStyle;
+
+use super::CursorShape;
+
+
+impl From<CursorShape> for SetCursorStyle {
+    fn from(value: CursorShape) -> Self {
+        match value {
+            CursorShape::Bar => SetCursorStyle::BlinkingBar,
+            CursorShape::Block => SetCursorStyle::BlinkingBlock,
+            CursorShape::Underline => SetCursorStyle::BlinkingUnderScore
+        }
+    }
+}
@@ -1,8 +1,28 @@
 mod widget;
 mod state;
 mod input;
+mod filter;
+mod search;
+mod sort;
+mod format;
+mod provider;
+mod operations;
+mod sidebar;
+mod preview;
+mod loader;
+mod theme;
+#[cfg(feature = "watch")]
+mod watcher;
 
 
-pub use state::{FileExplorerState, FileExplorerEvent};
-pub use widget::FileExplorer;
+pub use state::{FileDialogType, FileExplorerState, FileExplorerEvent, FileInformation, FileType};
+pub use widget::{FileExplorer, FileExplorerColumn};
+pub use filter::FileFilter;
+pub use operations::FileOperations;
+pub use theme::{FileExplorerTheme, IconSet};
+pub use sidebar::{BookmarkStore, FileBookmarkStore, MemoryBookmarkStore, Sidebar};
+pub use sort::{SortDirection, SortKey, SortOrder};
+pub use provider::{FileSystemProvider, LocalFileSystem, MemoryFileSystem};
+#[cfg(feature = "archive")]
+pub use provider::ArchiveFileSystem;
 pub use input::{FileExplorerCommand, DefaultFileExplorerInputConverter};
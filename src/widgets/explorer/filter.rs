@@ -0,0 +1,123 @@
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// File Filter
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFilter {
+    name: String,
+    patterns: Vec<String>,
+    // Patterns after brace expansion
+    expanded: Vec<Vec<char>>
+}
+
+impl FileFilter {
+    /// Create a filter from glob patterns such as `*.toml` or `*.{png,jpg}`.
+    /// Supported syntax: `*`, `?`, `[abc]`, `[a-z]`, `[!abc]` and `{a,b}`.
+    pub fn new<S: AsRef<str>>(name: &str, patterns: impl IntoIterator<Item=S>) -> Self {
+        let patterns = patterns.into_iter()
+            .map(|p| p.as_ref().to_string())
+            .collect::<Vec<_>>();
+        let expanded = patterns.iter()
+            .flat_map(|p| expand_braces(p))
+            .map(|p| p.chars().collect())
+            .collect();
+        Self { name: name.to_string(), patterns, expanded }
+    }
+
+    pub fn extensions<S: AsRef<str>>(name: &str, extensions: impl IntoIterator<Item=S>) -> Self {
+        let patterns = extensions.into_iter()
+            .map(|e| format!("*.{}", e.as_ref().trim_start_matches('.')))
+            .collect::<Vec<_>>();
+        Self::new(name, patterns)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn matches(&self, filename: &str) -> bool {
+        let filename = filename.chars().collect::<Vec<_>>();
+        self.expanded.iter().any(|pattern| glob_match(pattern, &filename))
+    }
+}
+
+impl std::fmt::Display for FileFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.patterns.join(", "))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Glob Matching
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let Some(close) = pattern[open..].find('}').map(|i| i + open) else {
+        return vec![pattern.to_string()];
+    };
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|alternative| expand_braces(&format!("{}{}{}", prefix, alternative, suffix)))
+        .collect()
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    // Iterative matching, backtracking to the most recent `*` on failure
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if pattern.get(p) == Some(&'*') {
+            star = Some((p, t));
+            p += 1;
+            continue;
+        }
+        let advance = match pattern.get(p) {
+            Some('?') => Some(1),
+            Some('[') => match_class(&pattern[p..], text[t]),
+            Some(c) if *c == text[t] => Some(1),
+            _ => None
+        };
+        if let Some(length) = advance {
+            p += length;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// Returns the length of the class in the pattern if `c` matches it.
+fn match_class(pattern: &[char], c: char) -> Option<usize> {
+    let end = pattern.iter().skip(2).position(|x| *x == ']')? + 2;
+    let (negated, body) = match pattern[1] {
+        '!' | '^' => (true, &pattern[2..end]),
+        _ => (false, &pattern[1..end])
+    };
+    let mut found = false;
+    let mut i = 0;
+    while i < body.len() {
+        if i + 2 < body.len() && body[i + 1] == '-' {
+            found |= body[i] <= c && c <= body[i + 2];
+            i += 3;
+        } else {
+            found |= body[i] == c;
+            i += 1;
+        }
+    }
+    (found != negated).then_some(end + 1)
+}
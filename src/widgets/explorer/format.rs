@@ -0,0 +1,57 @@
+use std::time::SystemTime;
+
+use crate::widgets::explorer::state::FileType;
+
+pub(super) fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{:.1}{}", value, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value, UNITS[unit])
+    }
+}
+
+pub(super) fn relative_time(time: SystemTime, now: SystemTime) -> String {
+    let Ok(elapsed) = now.duration_since(time) else {
+        return "future".to_string();
+    };
+    let seconds = elapsed.as_secs();
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86_399 => format!("{}h ago", seconds / 3600),
+        86_400..=2_591_999 => format!("{}d ago", seconds / 86_400),
+        2_592_000..=31_535_999 => format!("{}mo ago", seconds / 2_592_000),
+        _ => format!("{}y ago", seconds / 31_536_000)
+    }
+}
+
+pub(super) fn permissions(mode: u32, file_type: FileType) -> String {
+    let kind = match file_type {
+        FileType::File => '-',
+        FileType::Directory => 'd',
+        FileType::FileSymlink | FileType::DirectorySymlink | FileType::BrokenSymlink => 'l',
+        FileType::Socket => 's',
+        FileType::Fifo => 'p',
+        FileType::BlockDevice => 'b',
+        FileType::CharDevice => 'c'
+    };
+    let mut text = String::with_capacity(10);
+    text.push(kind);
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        text.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
@@ -1,6 +1,7 @@
 use crate::events::{Event, Key};
 use crate::widgets::core::RemyWidgetCommandConverter;
 use crate::widgets::explorer::FileExplorerState;
+use crate::widgets::explorer::state::{FileDialogType, FileExplorerPopup};
 use crate::widgets::text_input::{DefaultTextInputInputConverter, TextInputAction, TextInputState};
 use crate::widgets::util::cursor::TypingBehaviour;
 
@@ -8,12 +9,41 @@ use crate::widgets::util::cursor::TypingBehaviour;
 pub enum FileExplorerCommand {
     ArrowUp,
     ArrowDown,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    JumpToLetter(char),
     DirectoryUp,
     DirectoryDown,
     Exit,
     Confirm,
     EnterFileName,
-    FileNameDialogInput(TextInputAction)
+    Refresh,
+    ToggleHidden,
+    SwitchFocus,
+    SidebarUp,
+    SidebarDown,
+    SidebarOpen,
+    ToggleBookmark,
+    CycleFilter,
+    ToggleTreeMode,
+    ToggleMark,
+    MarkAll,
+    InvertMarks,
+    CycleSortMode,
+    ToggleSortDirection,
+    StartSearch(Option<char>),
+    SearchInput(TextInputAction),
+    EditPath,
+    PathInput(TextInputAction),
+    CreateDirectory,
+    Rename,
+    Delete,
+    CopyPath,
+    PopupInput(TextInputAction),
+    ConfirmPopup,
+    CancelPopup
 }
 
 pub struct DefaultFileExplorerInputConverter;
@@ -25,20 +55,89 @@ where
     type Event = FileExplorerCommand;
 
     fn convert(event: Event, state: &FileExplorerState<T>) -> Option<Self::Event> {
-        if let Some(s) = state.filename_input_state.as_ref() {
+        if let Some(FileExplorerPopup::ConfirmDelete(..) | FileExplorerPopup::ConfirmOverwrite(..)) = state.popup {
+            // Deleting takes an explicit y, so it can't happen by pressing Enter twice
+            let delete = matches!(state.popup, Some(FileExplorerPopup::ConfirmDelete(..)));
+            match event {
+                Event::KeyPress { key: Key::Character('y'), .. } => Some(FileExplorerCommand::ConfirmPopup),
+                Event::KeyPress { key: Key::Enter, .. } if !delete => Some(FileExplorerCommand::ConfirmPopup),
+                Event::KeyPress { key: Key::Esc | Key::Character('n'), .. } => Some(FileExplorerCommand::CancelPopup),
+                _ => None
+            }
+        } else if let Some(s) = state.popup.as_ref().and_then(|popup| popup.input()) {
+            let inner = <DefaultTextInputInputConverter as RemyWidgetCommandConverter<TextInputState<T>>>::convert(event, s);
+            inner.map(FileExplorerCommand::PopupInput)
+        } else if let Some(s) = state.path_input_state.as_ref() {
             let inner = <DefaultTextInputInputConverter as RemyWidgetCommandConverter<TextInputState<T>>>::convert(event, s);
-            inner.map(FileExplorerCommand::FileNameDialogInput)
+            inner.map(FileExplorerCommand::PathInput)
+        } else if let Some(s) = state.search_input_state.as_ref().filter(|_| state.search_focused) {
+            match event {
+                Event::KeyPress { key: Key::ArrowUp, .. } => Some(FileExplorerCommand::ArrowUp),
+                Event::KeyPress { key: Key::ArrowDown, .. } => Some(FileExplorerCommand::ArrowDown),
+                Event::KeyPress { key: Key::PageUp, .. } => Some(FileExplorerCommand::PageUp),
+                Event::KeyPress { key: Key::PageDown, .. } => Some(FileExplorerCommand::PageDown),
+                _ => <DefaultTextInputInputConverter as RemyWidgetCommandConverter<TextInputState<T>>>::convert(event, s)
+                    .map(FileExplorerCommand::SearchInput)
+            }
+        } else if state.sidebar_focused {
+            match event {
+                Event::KeyPress { key, ctrl, .. } => match key {
+                    Key::ArrowUp => Some(FileExplorerCommand::SidebarUp),
+                    Key::ArrowDown => Some(FileExplorerCommand::SidebarDown),
+                    Key::ArrowRight | Key::Enter => Some(FileExplorerCommand::SidebarOpen),
+                    Key::Tab | Key::BackTab => Some(FileExplorerCommand::SwitchFocus),
+                    Key::Character('b') if ctrl => Some(FileExplorerCommand::ToggleBookmark),
+                    Key::Esc => Some(FileExplorerCommand::Exit),
+                    _ => None
+                }
+                Event::Null => None
+            }
         } else {
+            let multiple = state.dialog_type.allow_multiple();
+            let save_as = state.dialog_type == FileDialogType::AskSaveAsFilename;
+            let filters = !state.filters.is_empty();
+            let sidebar = state.sidebar.is_some();
+            let operations = state.operations;
             match event {
-                Event::KeyPress { key, .. } => {
+                Event::KeyPress { key, ctrl, shift, alt, .. } => {
                     match key {
+                        Key::Esc if state.search_input_state.is_some() => {
+                            Some(FileExplorerCommand::SearchInput(TextInputAction::Esc))
+                        }
                         Key::ArrowUp => Some(FileExplorerCommand::ArrowUp),
                         Key::ArrowDown => Some(FileExplorerCommand::ArrowDown),
+                        Key::PageUp => Some(FileExplorerCommand::PageUp),
+                        Key::PageDown => Some(FileExplorerCommand::PageDown),
+                        Key::Home => Some(FileExplorerCommand::Home),
+                        Key::End => Some(FileExplorerCommand::End),
                         Key::ArrowLeft => Some(FileExplorerCommand::DirectoryUp),
                         Key::ArrowRight => Some(FileExplorerCommand::DirectoryDown),
                         Key::Enter => Some(FileExplorerCommand::Confirm),
+                        Key::F(3) if shift => Some(FileExplorerCommand::ToggleSortDirection),
+                        Key::F(3) => Some(FileExplorerCommand::CycleSortMode),
+                        Key::F(5) => Some(FileExplorerCommand::Refresh),
                         Key::Esc => Some(FileExplorerCommand::Exit),
-                        Key::Character('n') => Some(FileExplorerCommand::EnterFileName),
+                        Key::Character('n') if ctrl && save_as => Some(FileExplorerCommand::EnterFileName),
+                        Key::Character('h') if ctrl => Some(FileExplorerCommand::ToggleHidden),
+                        Key::Character('l') if ctrl => Some(FileExplorerCommand::EditPath),
+                        Key::Character('f') if ctrl && filters => Some(FileExplorerCommand::CycleFilter),
+                        Key::Character('t') if ctrl => Some(FileExplorerCommand::ToggleTreeMode),
+                        Key::Tab | Key::BackTab if sidebar => Some(FileExplorerCommand::SwitchFocus),
+                        Key::Character('b') if ctrl && sidebar => Some(FileExplorerCommand::ToggleBookmark),
+                        Key::Character(' ') if multiple => Some(FileExplorerCommand::ToggleMark),
+                        Key::Character('a') if ctrl && multiple => Some(FileExplorerCommand::MarkAll),
+                        Key::Character('r') if ctrl && multiple => Some(FileExplorerCommand::InvertMarks),
+                        Key::F(7) if operations.create_directory() => Some(FileExplorerCommand::CreateDirectory),
+                        Key::F(2) if operations.rename() => Some(FileExplorerCommand::Rename),
+                        Key::Delete if operations.delete() => Some(FileExplorerCommand::Delete),
+                        Key::Character('y') if ctrl && operations.copy_path() => Some(FileExplorerCommand::CopyPath),
+                        Key::Character('/') => Some(FileExplorerCommand::StartSearch(None)),
+                        Key::Character(c) if state.letter_jump && !ctrl && !alt && !c.is_whitespace() => {
+                            Some(FileExplorerCommand::JumpToLetter(c))
+                        }
+                        Key::Character(c) if !ctrl && !alt && !c.is_whitespace() => {
+                            Some(FileExplorerCommand::StartSearch(Some(c)))
+                        }
                         _ => None
                     }
                 }
@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::Instant;
+
+use crate::widgets::explorer::provider::FileSystemProvider;
+use crate::widgets::explorer::state::FileInformation;
+
+/// Called from the loading thread whenever a message is available.
+pub(super) type LoadNotifier = Arc<dyn Fn() + Send + Sync>;
+
+pub(super) enum LoadMessage {
+    Batch(Vec<FileInformation>),
+    Done,
+    Failed(String)
+}
+
+/// Reads a directory on a background thread. The scan is cancelled
+/// when the loader is dropped.
+pub(super) struct DirectoryLoader {
+    path: PathBuf,
+    receiver: Receiver<LoadMessage>,
+    cancelled: Arc<AtomicBool>,
+    // Entry to select once loading has finished
+    pub(super) select: Option<PathBuf>,
+    // Whether any message has been received yet
+    pub(super) started: bool,
+    pub(super) loaded: usize
+}
+
+impl DirectoryLoader {
+    pub(super) fn spawn(provider: Arc<dyn FileSystemProvider>,
+                        path: PathBuf,
+                        select: Option<PathBuf>,
+                        notify: Option<LoadNotifier>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread_path = path.clone();
+        let thread_cancelled = cancelled.clone();
+        std::thread::spawn(move || {
+            let send = |message| {
+                let sent = sender.send(message).is_ok();
+                if let Some(ref notify) = notify {
+                    notify();
+                }
+                sent
+            };
+            let result = provider.read_dir_batched(&thread_path, &mut |batch| {
+                !thread_cancelled.load(Ordering::Relaxed) && send(LoadMessage::Batch(batch))
+            });
+            if thread_cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+            send(match result {
+                Ok(()) => LoadMessage::Done,
+                Err(e) => LoadMessage::Failed(e.to_string())
+            });
+        });
+        Self { path, receiver, cancelled, select, started: false, loaded: 0 }
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the next message, if any. A disconnected channel
+    /// (i.e. a crashed scan) is reported as a failure.
+    pub(super) fn receive(&self, blocking: bool) -> Option<LoadMessage> {
+        let message = if blocking {
+            self.receiver.recv().map_err(|_| TryRecvError::Disconnected)
+        } else {
+            self.receiver.try_recv()
+        };
+        match message {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(self.disconnected())
+        }
+    }
+
+    /// Get the next message, waiting for it until the deadline.
+    pub(super) fn receive_until(&self, deadline: Instant) -> Option<LoadMessage> {
+        match self.receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(message) => Some(message),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(self.disconnected())
+        }
+    }
+
+    fn disconnected(&self) -> LoadMessage {
+        LoadMessage::Failed(format!("Failed to read {}", self.path.display()))
+    }
+}
+
+impl Drop for DirectoryLoader {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,65 @@
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// File Operations
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The file operations which the user may perform from the explorer.
+/// By default no operations are allowed, so the explorer is read-only.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct FileOperations {
+    create_directory: bool,
+    rename: bool,
+    delete: bool,
+    copy_path: bool
+}
+
+impl FileOperations {
+    pub fn read_only() -> Self {
+        Self::default()
+    }
+
+    pub fn all() -> Self {
+        Self {
+            create_directory: true,
+            rename: true,
+            delete: true,
+            copy_path: true
+        }
+    }
+
+    pub fn with_create_directory(mut self, allowed: bool) -> Self {
+        self.create_directory = allowed;
+        self
+    }
+
+    pub fn with_rename(mut self, allowed: bool) -> Self {
+        self.rename = allowed;
+        self
+    }
+
+    pub fn with_delete(mut self, allowed: bool) -> Self {
+        self.delete = allowed;
+        self
+    }
+
+    pub fn with_copy_path(mut self, allowed: bool) -> Self {
+        self.copy_path = allowed;
+        self
+    }
+
+    pub fn create_directory(&self) -> bool {
+        self.create_directory
+    }
+
+    pub fn rename(&self) -> bool {
+        self.rename
+    }
+
+    pub fn delete(&self) -> bool {
+        self.delete
+    }
+
+    pub fn copy_path(&self) -> bool {
+        self.copy_path
+    }
+}
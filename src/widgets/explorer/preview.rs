@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use crate::widgets::explorer::provider::FileSystemProvider;
+use crate::widgets::explorer::sort::SortOrder;
+use crate::widgets::explorer::state::{FileInformation, FileType};
+
+const HEX_BYTES_PER_LINE: usize = 8;
+// Entries listed for a directory; no preview pane is taller than this
+const MAX_DIRECTORY_ENTRIES: usize = 200;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Preview
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum PreviewContent {
+    Text(Vec<String>),
+    Binary(Vec<String>),
+    Directory(Vec<FileInformation>),
+    // Entries without readable contents, such as sockets and broken symlinks
+    Unavailable(FileType),
+    Error(String)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Preview {
+    pub(super) path: PathBuf,
+    pub(super) symlink_target: Option<PathBuf>,
+    pub(super) content: PreviewContent,
+    // Whether only part of the file was read
+    pub(super) truncated: bool
+}
+
+impl Preview {
+    pub(super) fn load(provider: &dyn FileSystemProvider,
+                       path: &Path,
+                       info: &FileInformation,
+                       sort_order: SortOrder,
+                       max_bytes: usize) -> Self {
+        let mut truncated = false;
+        let content = if info.file_type.is_dir() {
+            // Stop reading early, since this runs while rendering
+            let mut entries = Vec::new();
+            let result = provider.read_dir_batched(path, &mut |batch| {
+                entries.extend(batch);
+                entries.len() <= MAX_DIRECTORY_ENTRIES
+            });
+            match result {
+                Ok(()) => {
+                    truncated = entries.len() > MAX_DIRECTORY_ENTRIES;
+                    entries.truncate(MAX_DIRECTORY_ENTRIES);
+                    sort_order.sort(&mut entries);
+                    PreviewContent::Directory(entries)
+                }
+                Err(e) => PreviewContent::Error(e.to_string())
+            }
+        } else if !info.file_type.is_file() {
+            // Reading a FIFO or device could block or never end
+            PreviewContent::Unavailable(info.file_type)
+        } else {
+            // Read one byte more than shown to detect truncation
+            match provider.read(path, max_bytes.saturating_add(1)) {
+                Ok(mut bytes) => {
+                    truncated = bytes.len() > max_bytes;
+                    bytes.truncate(max_bytes);
+                    Self::decode(&bytes, truncated)
+                }
+                Err(e) => PreviewContent::Error(e.to_string())
+            }
+        };
+        Self {
+            path: path.to_path_buf(),
+            symlink_target: info.symlink_target.clone(),
+            content,
+            truncated
+        }
+    }
+
+    fn decode(bytes: &[u8], truncated: bool) -> PreviewContent {
+        if bytes.contains(&0) {
+            return PreviewContent::Binary(hex_dump(bytes));
+        }
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => text,
+            // The cut-off may have split a multibyte character
+            Err(e) if truncated && e.error_len().is_none() => {
+                std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap()
+            }
+            Err(_) => return PreviewContent::Binary(hex_dump(bytes))
+        };
+        PreviewContent::Text(
+            text.lines()
+                .map(|line| {
+                    line.replace('\t', "    ")
+                        .chars()
+                        .filter(|c| !c.is_control())
+                        .collect()
+                })
+                .collect()
+        )
+    }
+}
+
+fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    bytes.chunks(HEX_BYTES_PER_LINE)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let hex = chunk.iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let ascii = chunk.iter()
+                .map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' })
+                .collect::<String>();
+            format!(
+                "{:08x}  {:<width$}  {}",
+                index * HEX_BYTES_PER_LINE,
+                hex,
+                ascii,
+                width = HEX_BYTES_PER_LINE * 3 - 1
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::widgets::explorer::state::FileInformation;
+
+mod local;
+mod memory;
+#[cfg(feature = "archive")]
+mod archive;
+
+pub use local::LocalFileSystem;
+pub use memory::MemoryFileSystem;
+#[cfg(feature = "archive")]
+pub use archive::ArchiveFileSystem;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// File System Provider
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Source of the files shown in the file explorer.
+pub trait FileSystemProvider: Send + Sync {
+    /// List the entries of the given directory, in no particular order.
+    fn read_dir(&self, path: &Path) -> anyhow::Result<Vec<FileInformation>>;
+
+    /// Like `read_dir`, but hands the entries over in batches as they are read.
+    /// Reading stops early when `batch` returns `false`.
+    fn read_dir_batched(&self,
+                        path: &Path,
+                        batch: &mut dyn FnMut(Vec<FileInformation>) -> bool) -> anyhow::Result<()> {
+        batch(self.read_dir(path)?);
+        Ok(())
+    }
+
+    fn metadata(&self, path: &Path) -> anyhow::Result<FileInformation>;
+
+    /// Read at most `limit` bytes from the start of the given file.
+    fn read(&self, path: &Path, _limit: usize) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("Cannot read {}: not supported", path.display())
+    }
+
+    fn parent(&self, path: &Path) -> Option<PathBuf> {
+        path.parent().map(Path::to_path_buf)
+    }
+
+    fn create_dir(&self, path: &Path) -> anyhow::Result<()> {
+        anyhow::bail!("Cannot create {}: file system is read-only", path.display())
+    }
+
+    fn rename(&self, from: &Path, _to: &Path) -> anyhow::Result<()> {
+        anyhow::bail!("Cannot rename {}: file system is read-only", from.display())
+    }
+
+    /// Remove a file, or a directory including its contents.
+    fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        anyhow::bail!("Cannot delete {}: file system is read-only", path.display())
+    }
+}
+
+// Allows sharing a provider between the explorer and the application
+impl<P: FileSystemProvider + ?Sized> FileSystemProvider for Arc<P> {
+    fn read_dir(&self, path: &Path) -> anyhow::Result<Vec<FileInformation>> {
+        (**self).read_dir(path)
+    }
+
+    fn read_dir_batched(&self,
+                        path: &Path,
+                        batch: &mut dyn FnMut(Vec<FileInformation>) -> bool) -> anyhow::Result<()> {
+        (**self).read_dir_batched(path, batch)
+    }
+
+    fn metadata(&self, path: &Path) -> anyhow::Result<FileInformation> {
+        (**self).metadata(path)
+    }
+
+    fn read(&self, path: &Path, limit: usize) -> anyhow::Result<Vec<u8>> {
+        (**self).read(path, limit)
+    }
+
+    fn parent(&self, path: &Path) -> Option<PathBuf> {
+        (**self).parent(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> anyhow::Result<()> {
+        (**self).create_dir(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> anyhow::Result<()> {
+        (**self).rename(from, to)
+    }
+
+    fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        (**self).remove(path)
+    }
+}
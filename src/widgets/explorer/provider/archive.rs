@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::widgets::explorer::provider::{FileSystemProvider, MemoryFileSystem};
+use crate::widgets::explorer::state::{FileInformation, FileType};
+
+/// Read-only view of a tar (optionally gzip compressed) or zip archive.
+/// The archive is indexed once when it is opened; paths inside the
+/// archive are rooted at `/`.
+///
+/// Files in gzip compressed archives can't be read, and so not previewed,
+/// since that means decompressing everything in front of them.
+#[derive(Debug, Clone)]
+pub struct ArchiveFileSystem {
+    archive: PathBuf,
+    format: ArchiveFormat,
+    index: MemoryFileSystem,
+    tar_files: TarFiles
+}
+
+// Position and size of the data of each file in a plain tar archive
+type TarFiles = HashMap<PathBuf, (u64, u64)>;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip
+}
+
+impl ArchiveFileSystem {
+    /// Open an archive, detecting its format from the file extension
+    /// (`.tar`, `.tar.gz`, `.tgz` or `.zip`).
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let archive = path.into();
+        let name = archive.file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let format = if name.ends_with(".zip") {
+            ArchiveFormat::Zip
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            ArchiveFormat::TarGz
+        } else if name.ends_with(".tar") {
+            ArchiveFormat::Tar
+        } else {
+            anyhow::bail!("Unsupported archive format: {}", archive.display());
+        };
+        let file = BufReader::new(File::open(&archive)?);
+        let (index, tar_files) = match format {
+            ArchiveFormat::Tar => Self::index_tar(file)?,
+            ArchiveFormat::TarGz => (Self::index_tar(flate2::read::GzDecoder::new(file))?.0, HashMap::new()),
+            ArchiveFormat::Zip => (Self::index_zip(file)?, HashMap::new())
+        };
+        Ok(Self { archive, format, index, tar_files })
+    }
+
+    pub fn archive(&self) -> &Path {
+        &self.archive
+    }
+
+    // Also returns where the data of each file starts in the (uncompressed) archive
+    fn index_tar(reader: impl Read) -> anyhow::Result<(MemoryFileSystem, TarFiles)> {
+        let mut index = MemoryFileSystem::new();
+        let mut files = HashMap::new();
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let header = entry.header();
+            let file_type = match header.entry_type() {
+                tar::EntryType::Directory => FileType::Directory,
+                tar::EntryType::Regular | tar::EntryType::Continuous => FileType::File,
+                tar::EntryType::Symlink => FileType::FileSymlink,
+                _ => continue
+            };
+            let symlink_target = if file_type.is_symlink() {
+                entry.link_name()?.map(|target| target.into_owned())
+            } else {
+                None
+            };
+            let owner = header.uid().ok()
+                .zip(header.gid().ok())
+                .map(|(uid, gid)| (uid as u32, gid as u32));
+            let info = FileInformation::new("", file_type)
+                .with_size(header.size().unwrap_or(0))
+                .with_modified(header.mtime().ok().map(|t| SystemTime::UNIX_EPOCH + Duration::from_secs(t)))
+                .with_permissions(header.mode().ok())
+                .with_owner(owner)
+                .with_symlink_target(symlink_target);
+            let path = entry.path()?;
+            if file_type.is_file() {
+                files.insert(normalize(&path), (entry.raw_file_position(), entry.size()));
+            }
+            index.insert(&path, info, Vec::new());
+        }
+        Ok((index, files))
+    }
+
+    fn index_zip(reader: BufReader<File>) -> anyhow::Result<MemoryFileSystem> {
+        let mut index = MemoryFileSystem::new();
+        let mut archive = zip::ZipArchive::new(reader)?;
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i)?;
+            // Skip entries with unsafe paths, e.g. containing `..`
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let file_type = if entry.is_dir() { FileType::Directory } else { FileType::File };
+            let info = FileInformation::new("", file_type)
+                .with_size(entry.size())
+                .with_permissions(entry.unix_mode());
+            index.insert(&path, info, Vec::new());
+        }
+        Ok(index)
+    }
+
+    fn read_tar(&self, mut reader: BufReader<File>, path: &Path, limit: usize) -> anyhow::Result<Vec<u8>> {
+        let Some(&(position, size)) = self.tar_files.get(&normalize(path)) else {
+            anyhow::bail!("No such file: {}", path.display())
+        };
+        reader.seek(SeekFrom::Start(position))?;
+        let mut buffer = Vec::new();
+        reader.take(size.min(limit as u64)).read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn read_zip(reader: BufReader<File>, path: &Path, limit: usize) -> anyhow::Result<Vec<u8>> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i)?;
+            if entry.enclosed_name().is_some_and(|name| same_path(&name, path)) {
+                let mut buffer = Vec::new();
+                entry.take(limit as u64).read_to_end(&mut buffer)?;
+                return Ok(buffer);
+            }
+        }
+        anyhow::bail!("No such file: {}", path.display())
+    }
+}
+
+// Keep only the normal components, since archive entries are relative.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    normalize(a) == normalize(b)
+}
+
+impl FileSystemProvider for ArchiveFileSystem {
+    fn read_dir(&self, path: &Path) -> anyhow::Result<Vec<FileInformation>> {
+        self.index.read_dir(path)
+    }
+
+    fn metadata(&self, path: &Path) -> anyhow::Result<FileInformation> {
+        self.index.metadata(path)
+    }
+
+    fn read(&self, path: &Path, limit: usize) -> anyhow::Result<Vec<u8>> {
+        if !self.index.metadata(path)?.file_type.is_file() {
+            anyhow::bail!("Not a file: {}", path.display());
+        }
+        match self.format {
+            ArchiveFormat::Tar => self.read_tar(BufReader::new(File::open(&self.archive)?), path, limit),
+            ArchiveFormat::TarGz => anyhow::bail!("Cannot read {}: the archive is compressed", path.display()),
+            ArchiveFormat::Zip => Self::read_zip(BufReader::new(File::open(&self.archive)?), path, limit)
+        }
+    }
+
+    fn parent(&self, path: &Path) -> Option<PathBuf> {
+        self.index.parent(path)
+    }
+}
@@ -0,0 +1,145 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::widgets::explorer::provider::FileSystemProvider;
+use crate::widgets::explorer::state::{FileInformation, FileType};
+
+const BATCH_SIZE: usize = 256;
+
+/// The file system of the local machine.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LocalFileSystem;
+
+impl LocalFileSystem {
+    fn file_information(path: &Path, name: OsString) -> anyhow::Result<FileInformation> {
+        let metadata = fs::symlink_metadata(path)?;
+        let (file_type, metadata) = if metadata.is_symlink() {
+            match fs::metadata(path) {
+                Ok(target) if target.is_dir() => (FileType::DirectorySymlink, target),
+                Ok(target) => (FileType::FileSymlink, target),
+                Err(_) => (FileType::BrokenSymlink, metadata)
+            }
+        } else {
+            (file_type(metadata.file_type()), metadata)
+        };
+        let symlink_target = if file_type.is_symlink() {
+            fs::read_link(path).ok()
+        } else {
+            None
+        };
+        let (permissions, owner) = unix_metadata(&metadata);
+        Ok(
+            FileInformation::new(name, file_type)
+                .with_size(metadata.len())
+                .with_modified(metadata.modified().ok())
+                .with_permissions(permissions)
+                .with_owner(owner)
+                .with_symlink_target(symlink_target)
+        )
+    }
+
+    // An entry which can't be stat'ed is still listed, with only the type
+    // reported by the directory itself. Unreadable entries are skipped.
+    fn entry_information(entry: std::io::Result<fs::DirEntry>) -> Option<FileInformation> {
+        let entry = entry.ok()?;
+        Self::file_information(&entry.path(), entry.file_name())
+            .ok()
+            .or_else(|| {
+                let kind = entry.file_type().map_or(FileType::File, file_type);
+                Some(FileInformation::new(entry.file_name(), kind))
+            })
+    }
+}
+
+impl FileSystemProvider for LocalFileSystem {
+    fn read_dir(&self, path: &Path) -> anyhow::Result<Vec<FileInformation>> {
+        Ok(fs::read_dir(path)?.filter_map(Self::entry_information).collect())
+    }
+
+    fn read_dir_batched(&self,
+                        path: &Path,
+                        batch: &mut dyn FnMut(Vec<FileInformation>) -> bool) -> anyhow::Result<()> {
+        let mut files = Vec::with_capacity(BATCH_SIZE);
+        for info in fs::read_dir(path)?.filter_map(Self::entry_information) {
+            files.push(info);
+            if files.len() == BATCH_SIZE && !batch(std::mem::replace(&mut files, Vec::with_capacity(BATCH_SIZE))) {
+                return Ok(());
+            }
+        }
+        if !files.is_empty() {
+            batch(files);
+        }
+        Ok(())
+    }
+
+    fn metadata(&self, path: &Path) -> anyhow::Result<FileInformation> {
+        let name = path.file_name()
+            .unwrap_or(path.as_os_str())
+            .to_os_string();
+        Self::file_information(path, name)
+    }
+
+    fn read(&self, path: &Path, limit: usize) -> anyhow::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        fs::File::open(path)?
+            .take(limit as u64)
+            .read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn create_dir(&self, path: &Path) -> anyhow::Result<()> {
+        Ok(fs::create_dir(path)?)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> anyhow::Result<()> {
+        if fs::symlink_metadata(to).is_ok() {
+            anyhow::bail!("{} already exists", to.display());
+        }
+        Ok(fs::rename(from, to)?)
+    }
+
+    fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        if fs::symlink_metadata(path)?.is_dir() {
+            fs::remove_dir_all(path)?;
+        } else {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn file_type(file_type: fs::FileType) -> FileType {
+    use std::os::unix::fs::FileTypeExt;
+    if file_type.is_dir() {
+        FileType::Directory
+    } else if file_type.is_socket() {
+        FileType::Socket
+    } else if file_type.is_fifo() {
+        FileType::Fifo
+    } else if file_type.is_block_device() {
+        FileType::BlockDevice
+    } else if file_type.is_char_device() {
+        FileType::CharDevice
+    } else {
+        FileType::File
+    }
+}
+
+#[cfg(not(unix))]
+fn file_type(file_type: fs::FileType) -> FileType {
+    if file_type.is_dir() { FileType::Directory } else { FileType::File }
+}
+
+#[cfg(unix)]
+fn unix_metadata(metadata: &fs::Metadata) -> (Option<u32>, Option<(u32, u32)>) {
+    use std::os::unix::fs::MetadataExt;
+    (Some(metadata.mode()), Some((metadata.uid(), metadata.gid())))
+}
+
+#[cfg(not(unix))]
+fn unix_metadata(_metadata: &fs::Metadata) -> (Option<u32>, Option<(u32, u32)>) {
+    (None, None)
+}
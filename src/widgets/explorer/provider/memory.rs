@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::widgets::explorer::provider::FileSystemProvider;
+use crate::widgets::explorer::state::{FileInformation, FileType};
+
+#[derive(Debug, Clone)]
+struct MemoryEntry {
+    info: FileInformation,
+    contents: Vec<u8>
+}
+
+/// A file system tree kept entirely in memory, rooted at `/`.
+/// Missing parent directories are created on insertion.
+#[derive(Debug)]
+pub struct MemoryFileSystem {
+    entries: RwLock<BTreeMap<PathBuf, MemoryEntry>>
+}
+
+impl Default for MemoryFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for MemoryFileSystem {
+    fn clone(&self) -> Self {
+        Self { entries: RwLock::new(self.entries.read().unwrap().clone()) }
+    }
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        let mut entries = BTreeMap::new();
+        entries.insert(
+            PathBuf::from("/"),
+            MemoryEntry {
+                info: FileInformation::new("/", FileType::Directory),
+                contents: Vec::new()
+            }
+        );
+        Self { entries: RwLock::new(entries) }
+    }
+
+    pub fn with_directory(mut self, path: impl AsRef<Path>) -> Self {
+        self.add_directory(path);
+        self
+    }
+
+    pub fn with_file(mut self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) -> Self {
+        self.add_file(path, contents);
+        self
+    }
+
+    pub fn add_directory(&mut self, path: impl AsRef<Path>) {
+        self.insert(path.as_ref(), FileInformation::new("", FileType::Directory), Vec::new());
+    }
+
+    pub fn add_file(&mut self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) {
+        let contents = contents.into();
+        let info = FileInformation::new("", FileType::File)
+            .with_size(contents.len() as u64);
+        self.insert(path.as_ref(), info, contents);
+    }
+
+    pub fn contents(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.entries.read().unwrap()
+            .get(&normalise(path.as_ref()))
+            .filter(|entry| entry.info.file_type.is_file())
+            .map(|entry| entry.contents.clone())
+    }
+
+    // The name of the given information is replaced by the last component of the path.
+    pub(super) fn insert(&mut self, path: &Path, info: FileInformation, contents: Vec<u8>) {
+        Self::insert_entry(self.entries.get_mut().unwrap(), path, info, contents);
+    }
+
+    fn insert_entry(entries: &mut BTreeMap<PathBuf, MemoryEntry>,
+                    path: &Path,
+                    mut info: FileInformation,
+                    contents: Vec<u8>) {
+        let path = normalise(path);
+        let Some(parent) = path.parent() else {
+            return;     // The root always exists
+        };
+        if !entries.contains_key(parent) {
+            let directory = FileInformation::new("", FileType::Directory);
+            Self::insert_entry(entries, parent, directory, Vec::new());
+        }
+        info.name = path.file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_default();
+        entries.insert(path, MemoryEntry { info, contents });
+    }
+}
+
+impl FileSystemProvider for MemoryFileSystem {
+    fn read_dir(&self, path: &Path) -> anyhow::Result<Vec<FileInformation>> {
+        let path = normalise(path);
+        let entries = self.entries.read().unwrap();
+        match entries.get(&path) {
+            Some(entry) if entry.info.file_type.is_dir() => (),
+            Some(_) => anyhow::bail!("Not a directory: {}", path.display()),
+            None => anyhow::bail!("No such directory: {}", path.display())
+        }
+        Ok(
+            entries.iter()
+                .filter(|(p, _)| p.parent() == Some(path.as_path()))
+                .map(|(_, entry)| entry.info.clone())
+                .collect()
+        )
+    }
+
+    fn metadata(&self, path: &Path) -> anyhow::Result<FileInformation> {
+        let path = normalise(path);
+        self.entries.read().unwrap()
+            .get(&path)
+            .map(|entry| entry.info.clone())
+            .ok_or_else(|| anyhow::anyhow!("No such file or directory: {}", path.display()))
+    }
+
+    fn read(&self, path: &Path, limit: usize) -> anyhow::Result<Vec<u8>> {
+        let path = normalise(path);
+        match self.entries.read().unwrap().get(&path) {
+            Some(entry) if entry.info.file_type.is_file() => {
+                Ok(entry.contents.iter().take(limit).copied().collect())
+            }
+            Some(_) => anyhow::bail!("Not a file: {}", path.display()),
+            None => anyhow::bail!("No such file: {}", path.display())
+        }
+    }
+
+    fn parent(&self, path: &Path) -> Option<PathBuf> {
+        normalise(path).parent().map(Path::to_path_buf)
+    }
+
+    fn create_dir(&self, path: &Path) -> anyhow::Result<()> {
+        let path = normalise(path);
+        let mut entries = self.entries.write().unwrap();
+        if entries.contains_key(&path) {
+            anyhow::bail!("{} already exists", path.display());
+        }
+        if !path.parent().is_some_and(|parent| entries.contains_key(parent)) {
+            anyhow::bail!("No such directory: {}", path.display());
+        }
+        let directory = FileInformation::new("", FileType::Directory);
+        Self::insert_entry(&mut entries, &path, directory, Vec::new());
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> anyhow::Result<()> {
+        let (from, to) = (normalise(from), normalise(to));
+        let mut entries = self.entries.write().unwrap();
+        if !entries.contains_key(&from) {
+            anyhow::bail!("No such file or directory: {}", from.display());
+        }
+        if entries.contains_key(&to) {
+            anyhow::bail!("{} already exists", to.display());
+        }
+        if to.starts_with(&from) {
+            anyhow::bail!("Cannot move {} into itself", from.display());
+        }
+        let moved = entries.keys()
+            .filter(|path| path.starts_with(&from))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in moved {
+            let entry = entries.remove(&path).unwrap();
+            let target = to.join(path.strip_prefix(&from).unwrap());
+            Self::insert_entry(&mut entries, &target, entry.info, entry.contents);
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        let path = normalise(path);
+        if path.parent().is_none() {
+            anyhow::bail!("Cannot delete the root directory");
+        }
+        let mut entries = self.entries.write().unwrap();
+        if !entries.contains_key(&path) {
+            anyhow::bail!("No such file or directory: {}", path.display());
+        }
+        entries.retain(|p, _| !p.starts_with(&path));
+        Ok(())
+    }
+}
+
+// Make the path absolute and resolve `.` and `..` components.
+fn normalise(path: &Path) -> PathBuf {
+    let mut result = PathBuf::from("/");
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => result.push(name),
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => ()
+        }
+    }
+    result
+}
@@ -0,0 +1,13 @@
+/// Case-insensitive subsequence match of `query` in `name`.
+/// Returns the character indices in `name` which matched the query.
+pub(super) fn fuzzy_match(query: &str, name: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut candidates = name.chars().enumerate();
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let (index, _) = candidates
+            .by_ref()
+            .find(|(_, c)| c.to_lowercase().eq(q.to_lowercase()))?;
+        positions.push(index);
+    }
+    Some(positions)
+}
@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use ratatui::widgets::ListState;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Bookmark Stores
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Persistent storage for the bookmarks shown in the sidebar.
+pub trait BookmarkStore: Send {
+    fn load(&self) -> anyhow::Result<Vec<PathBuf>>;
+
+    fn save(&self, bookmarks: &[PathBuf]) -> anyhow::Result<()>;
+}
+
+/// Keeps the bookmarks for the lifetime of the store only.
+#[derive(Debug, Default)]
+pub struct MemoryBookmarkStore {
+    bookmarks: RwLock<Vec<PathBuf>>
+}
+
+impl MemoryBookmarkStore {
+    pub fn new(bookmarks: Vec<PathBuf>) -> Self {
+        Self { bookmarks: RwLock::new(bookmarks) }
+    }
+}
+
+impl BookmarkStore for MemoryBookmarkStore {
+    fn load(&self) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(self.bookmarks.read().unwrap().clone())
+    }
+
+    fn save(&self, bookmarks: &[PathBuf]) -> anyhow::Result<()> {
+        *self.bookmarks.write().unwrap() = bookmarks.to_vec();
+        Ok(())
+    }
+}
+
+/// Stores the bookmarks in a text file, one path per line.
+/// A missing file is treated as having no bookmarks.
+#[derive(Debug, Clone)]
+pub struct FileBookmarkStore {
+    path: PathBuf
+}
+
+impl FileBookmarkStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl BookmarkStore for FileBookmarkStore {
+    fn load(&self) -> anyhow::Result<Vec<PathBuf>> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Ok(
+                text.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(PathBuf::from)
+                    .collect()
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into())
+        }
+    }
+
+    fn save(&self, bookmarks: &[PathBuf]) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = bookmarks.iter()
+            .map(|path| format!("{}\n", path.display()))
+            .collect::<String>();
+        fs::write(&self.path, text)?;
+        Ok(())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Sidebar
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum SidebarSection {
+    Places,
+    Volumes,
+    Bookmarks,
+    Recent
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SidebarEntry {
+    pub(super) section: SidebarSection,
+    pub(super) label: String,
+    pub(super) path: PathBuf
+}
+
+/// Quick-jump locations shown next to the file list: home and root,
+/// mounted volumes, bookmarks and recently visited directories.
+pub struct Sidebar {
+    places: Vec<(String, PathBuf)>,
+    volumes: Vec<PathBuf>,
+    store: Box<dyn BookmarkStore>,
+    bookmarks: Vec<PathBuf>,
+    recent: VecDeque<PathBuf>,
+    max_recent: usize,
+    pub(super) list_state: ListState
+}
+
+impl Default for Sidebar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sidebar {
+    pub fn new() -> Self {
+        let mut places = Vec::new();
+        if let Some(home) = home_directory() {
+            places.push(("Home".to_string(), home));
+        }
+        places.push(("Root".to_string(), PathBuf::from("/")));
+        Self {
+            places,
+            volumes: mounted_volumes(),
+            store: Box::new(MemoryBookmarkStore::default()),
+            bookmarks: Vec::new(),
+            recent: VecDeque::new(),
+            max_recent: 10,
+            list_state: ListState::default().with_selected(Some(0))
+        }
+    }
+
+    /// Replace the default places (home and root), e.g. when
+    /// browsing something other than the local file system.
+    pub fn with_places(mut self, places: Vec<(String, PathBuf)>) -> Self {
+        self.places = places;
+        self
+    }
+
+    pub fn with_volumes(mut self, show_volumes: bool) -> Self {
+        self.volumes = if show_volumes { mounted_volumes() } else { Vec::new() };
+        self
+    }
+
+    pub fn with_max_recent(mut self, max_recent: usize) -> Self {
+        self.max_recent = max_recent;
+        self.recent.truncate(max_recent);
+        self
+    }
+
+    /// Use the given store for the bookmarks, loading the bookmarks from it.
+    pub fn with_bookmark_store(mut self, store: impl BookmarkStore + 'static) -> anyhow::Result<Self> {
+        self.bookmarks = store.load()?;
+        self.store = Box::new(store);
+        Ok(self)
+    }
+
+    pub fn bookmarks(&self) -> &[PathBuf] {
+        &self.bookmarks
+    }
+
+    pub fn recent(&self) -> impl Iterator<Item=&Path> {
+        self.recent.iter().map(|path| path.as_path())
+    }
+
+    pub(super) fn entries(&self) -> Vec<SidebarEntry> {
+        let places = self.places.iter()
+            .map(|(label, path)| SidebarEntry {
+                section: SidebarSection::Places,
+                label: label.clone(),
+                path: path.clone()
+            });
+        let volumes = self.volumes.iter()
+            .map(|path| Self::entry(SidebarSection::Volumes, path));
+        let bookmarks = self.bookmarks.iter()
+            .map(|path| Self::entry(SidebarSection::Bookmarks, path));
+        let recent = self.recent.iter()
+            .map(|path| Self::entry(SidebarSection::Recent, path));
+        places.chain(volumes).chain(bookmarks).chain(recent).collect()
+    }
+
+    fn entry(section: SidebarSection, path: &Path) -> SidebarEntry {
+        let label = path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        SidebarEntry { section, label, path: path.to_path_buf() }
+    }
+
+    pub(super) fn selected_path(&self) -> Option<PathBuf> {
+        self.list_state.selected()
+            .and_then(|index| self.entries().into_iter().nth(index))
+            .map(|entry| entry.path)
+    }
+
+    pub(super) fn move_selection(&mut self, offset: isize) {
+        let count = self.entries().len();
+        if count == 0 {
+            self.list_state.select(None);
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0);
+        let next = current.saturating_add_signed(offset).min(count - 1);
+        self.list_state.select(Some(next));
+    }
+
+    /// Add the directory to the bookmarks, or remove it if it is already bookmarked.
+    pub(super) fn toggle_bookmark(&mut self, path: &Path) -> anyhow::Result<()> {
+        match self.bookmarks.iter().position(|bookmark| bookmark == path) {
+            Some(index) => {
+                self.bookmarks.remove(index);
+            }
+            None => self.bookmarks.push(path.to_path_buf())
+        }
+        self.store.save(&self.bookmarks)
+    }
+
+    pub(super) fn visit(&mut self, path: &Path) {
+        self.recent.retain(|recent| recent != path);
+        self.recent.push_front(path.to_path_buf());
+        self.recent.truncate(self.max_recent);
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Locations
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn home_directory() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+// Mount points of block devices, excluding the root file system.
+#[cfg(target_os = "linux")]
+fn mounted_volumes() -> Vec<PathBuf> {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+    mounts.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            let mount_point = unescape_mount_point(fields.next()?);
+            (device.starts_with("/dev/") && mount_point != "/").then(|| PathBuf::from(mount_point))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mounted_volumes() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+// Spaces and other special characters are escaped as octal, e.g. `\040`.
+#[cfg(target_os = "linux")]
+fn unescape_mount_point(field: &str) -> String {
+    let mut result = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let digits = chars.clone().take(3).collect::<String>();
+        match u8::from_str_radix(&digits, 8) {
+            Ok(value) if digits.len() == 3 => {
+                result.push(value as char);
+                chars.nth(2);
+            }
+            _ => result.push(c)
+        }
+    }
+    result
+}
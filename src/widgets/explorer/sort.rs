@@ -0,0 +1,236 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use crate::widgets::explorer::state::FileInformation;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Sort Settings
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    NaturalName,
+    Size,
+    Modified,
+    Extension
+}
+
+impl SortKey {
+    pub fn next(&self) -> Self {
+        match self {
+            SortKey::Name => SortKey::NaturalName,
+            SortKey::NaturalName => SortKey::Size,
+            SortKey::Size => SortKey::Modified,
+            SortKey::Modified => SortKey::Extension,
+            SortKey::Extension => SortKey::Name
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::NaturalName => "natural",
+            SortKey::Size => "size",
+            SortKey::Modified => "modified",
+            SortKey::Extension => "extension"
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending
+}
+
+impl SortDirection {
+    pub fn reversed(&self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SortOrder {
+    key: SortKey,
+    direction: SortDirection,
+    directories_first: bool,
+    ignore_case: bool
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        Self::new(SortKey::Name)
+    }
+}
+
+impl SortOrder {
+    pub fn new(key: SortKey) -> Self {
+        Self {
+            key,
+            direction: SortDirection::Ascending,
+            directories_first: true,
+            ignore_case: false
+        }
+    }
+
+    pub fn with_key(mut self, key: SortKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: SortDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_directories_first(mut self, directories_first: bool) -> Self {
+        self.directories_first = directories_first;
+        self
+    }
+
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    pub fn key(&self) -> SortKey {
+        self.key
+    }
+
+    pub fn direction(&self) -> SortDirection {
+        self.direction
+    }
+
+    pub fn directories_first(&self) -> bool {
+        self.directories_first
+    }
+
+    pub fn ignore_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    /// Sort the entries, computing the (possibly lowercased) names
+    /// once per entry rather than once per comparison.
+    pub(super) fn sort(&self, entries: &mut Vec<FileInformation>) {
+        let keys = entries.iter().map(|info| self.cached_key(info)).collect::<Vec<_>>();
+        let mut order = (0..keys.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| self.compare_keys(&keys[*a], &keys[*b]));
+        drop(keys);
+        let mut unsorted = std::mem::take(entries).into_iter().map(Some).collect::<Vec<_>>();
+        entries.extend(order.into_iter().filter_map(|index| unsorted[index].take()));
+    }
+
+    fn cached_key<'a>(&self, info: &'a FileInformation) -> CachedKey<'a> {
+        let name = self.normalise(&info.display_name());
+        let extension = match self.key {
+            SortKey::Extension => extension(&name),
+            _ => String::new()
+        };
+        CachedKey { info, name, extension }
+    }
+
+    fn compare_keys(&self, a: &CachedKey, b: &CachedKey) -> Ordering {
+        if self.directories_first {
+            match b.info.file_type.is_dir().cmp(&a.info.file_type.is_dir()) {
+                Ordering::Equal => (),
+                o => return o
+            }
+        }
+        let ordering = match self.key {
+            SortKey::Name => Ordering::Equal,
+            SortKey::NaturalName => natural_cmp(&a.name, &b.name),
+            SortKey::Size => a.info.size.cmp(&b.info.size),
+            SortKey::Modified => a.info.modified.cmp(&b.info.modified),
+            SortKey::Extension => a.extension.cmp(&b.extension)
+        }.then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.info.name.cmp(&b.info.name));
+        match self.direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse()
+        }
+    }
+
+    fn normalise(&self, name: &str) -> String {
+        if self.ignore_case {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+// The parts of an entry compared when sorting
+struct CachedKey<'a> {
+    info: &'a FileInformation,
+    name: String,
+    extension: String
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let arrow = match self.direction {
+            SortDirection::Ascending => '\u{2191}',
+            SortDirection::Descending => '\u{2193}'
+        };
+        write!(f, "{} {}", self.key.label(), arrow)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Comparison Helpers
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// Compare strings such that runs of digits are compared by numeric value,
+// e.g. `file2` < `file10`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let first = take_number(&mut a);
+                let second = take_number(&mut b);
+                let ordering = first.len()
+                    .cmp(&second.len())
+                    .then_with(|| first.cmp(&second));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+    // Leading zeros do not change the value
+    let trimmed = digits.trim_start_matches('0');
+    trimmed.to_string()
+}
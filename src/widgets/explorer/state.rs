@@ -1,14 +1,33 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
+use arboard::Clipboard;
 use ratatui::widgets::TableState;
 
 use crate::widgets::core::RemyWidgetState;
+use crate::widgets::explorer::filter::FileFilter;
 use crate::widgets::explorer::input::FileExplorerCommand;
-use crate::widgets::text_input::{TextInputEvent, TextInputState};
+use crate::widgets::explorer::loader::{DirectoryLoader, LoadMessage, LoadNotifier};
+use crate::widgets::explorer::operations::FileOperations;
+use crate::widgets::explorer::preview::Preview;
+use crate::widgets::explorer::provider::{FileSystemProvider, LocalFileSystem};
+use crate::widgets::explorer::search::fuzzy_match;
+use crate::widgets::explorer::sidebar::{home_directory, Sidebar};
+#[cfg(feature = "watch")]
+use crate::widgets::explorer::watcher::{DirectoryWatcher, WatchChange};
+use crate::widgets::explorer::sort::SortOrder;
+use crate::widgets::text_input::{TextInputAction, TextInputEvent, TextInputState};
+use crate::widgets::util::cursor::UserAction;
 use crate::widgets::util::cursor::TypingBehaviour;
 
+// How long opening a directory blocks before the rest is read in the background
+const LOAD_WAIT: Duration = Duration::from_millis(100);
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 //////////////////////////////////////////////////////////////////////////////////////////////////
 // File Information Structs
@@ -17,58 +36,142 @@ use crate::widgets::util::cursor::TypingBehaviour;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileInformation {
     pub(super) file_type: FileType,
-    pub(super) name: String,
+    pub(super) name: OsString,
+    pub(super) size: u64,
+    pub(super) modified: Option<SystemTime>,
+    pub(super) permissions: Option<u32>,
+    pub(super) owner: Option<(u32, u32)>,
+    pub(super) symlink_target: Option<PathBuf>,
 }
 
-impl Ord for FileInformation {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.file_type.cmp(&other.file_type) {
-            Ordering::Equal => self.name.cmp(&other.name),
-            o => o
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FileType {
+    File,
+    Directory,
+    FileSymlink,
+    DirectorySymlink,
+    // A symlink whose target does not exist or cannot be accessed
+    BrokenSymlink,
+    Socket,
+    Fifo,
+    BlockDevice,
+    CharDevice
+}
+
+impl FileInformation {
+    pub fn new(name: impl Into<OsString>, file_type: FileType) -> Self {
+        Self {
+            file_type,
+            name: name.into(),
+            size: 0,
+            modified: None,
+            permissions: None,
+            owner: None,
+            symlink_target: None
         }
     }
-}
 
-impl PartialOrd for FileInformation {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = size;
+        self
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub(super) enum FileType {
-    File,
-    Directory,
-    FileSymlink,
-    DirectorySymlink
+    pub fn with_modified(mut self, modified: Option<SystemTime>) -> Self {
+        self.modified = modified;
+        self
+    }
+
+    pub fn with_permissions(mut self, permissions: Option<u32>) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_owner(mut self, owner: Option<(u32, u32)>) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    pub fn with_symlink_target(mut self, target: Option<PathBuf>) -> Self {
+        self.symlink_target = target;
+        self
+    }
+
+    pub fn name(&self) -> &OsStr {
+        &self.name
+    }
+
+    /// The name for display, with invalid UTF-8 replaced.
+    pub fn display_name(&self) -> Cow<'_, str> {
+        self.name.to_string_lossy()
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    pub(super) fn is_hidden(&self) -> bool {
+        self.name.as_encoded_bytes().starts_with(b".")
+    }
 }
 
 impl FileType {
-    pub(super) fn is_symlink(&self) -> bool {
-        matches!(self, FileType::FileSymlink | FileType::DirectorySymlink)
+    pub fn is_symlink(&self) -> bool {
+        matches!(self, FileType::FileSymlink | FileType::DirectorySymlink | FileType::BrokenSymlink)
     }
 
-    pub(super) fn is_dir(&self) -> bool {
+    pub fn is_dir(&self) -> bool {
         matches!(self, FileType::Directory | FileType::DirectorySymlink)
     }
 
-    pub(super) fn is_file(&self) -> bool {
+    pub fn is_file(&self) -> bool {
         matches!(self, FileType::File | FileType::FileSymlink)
     }
+
+    /// Sockets, FIFOs and devices, which cannot be read like regular files.
+    pub fn is_special(&self) -> bool {
+        matches!(self, FileType::Socket | FileType::Fifo | FileType::BlockDevice | FileType::CharDevice)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            FileType::File => "file",
+            FileType::Directory => "directory",
+            FileType::FileSymlink => "symlink to file",
+            FileType::DirectorySymlink => "symlink to directory",
+            FileType::BrokenSymlink => "broken symlink",
+            FileType::Socket => "socket",
+            FileType::Fifo => "FIFO",
+            FileType::BlockDevice => "block device",
+            FileType::CharDevice => "character device"
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            FileType::File => 0,
+            FileType::FileSymlink => 1,
+            FileType::Directory => 2,
+            FileType::DirectorySymlink => 3,
+            FileType::BrokenSymlink => 4,
+            FileType::Socket => 5,
+            FileType::Fifo => 6,
+            FileType::BlockDevice => 7,
+            FileType::CharDevice => 8
+        }
+    }
 }
 
 impl Ord for FileType {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (x, y) if x == y => Ordering::Equal,
-            (FileType::File, _) => Ordering::Less,
-            (_, FileType::File) => Ordering::Greater,
-            (FileType::FileSymlink, _) => Ordering::Less,
-            (_, FileType::FileSymlink) => Ordering::Greater,
-            (FileType::Directory, _) => Ordering::Less,
-            (_, FileType::Directory) => Ordering::Greater,
-            (FileType::DirectorySymlink, _) => Ordering::Less
-        }
+        self.rank().cmp(&other.rank())
     }
 }
 
@@ -78,6 +181,19 @@ impl PartialOrd for FileType {
     }
 }
 
+/// An entry as shown in the table. In tree mode, entries of
+/// expanded directories are shown below their parent.
+#[derive(Debug, Clone)]
+pub(super) struct FileRow {
+    pub(super) info: FileInformation,
+    pub(super) path: PathBuf,
+    pub(super) depth: usize,
+    // For every ancestor level, whether that ancestor has siblings below it
+    pub(super) guides: Vec<bool>,
+    pub(super) last: bool,
+    pub(super) expanded: bool
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 //////////////////////////////////////////////////////////////////////////////////////////////////
 // Dialog type
@@ -88,7 +204,8 @@ impl PartialOrd for FileType {
 pub enum FileDialogType {
     AskFilename,
     AskDirectory,
-    AskSaveAsFilename
+    AskSaveAsFilename,
+    AskMultipleFilenames
 }
 
 impl FileDialogType {
@@ -96,7 +213,8 @@ impl FileDialogType {
         match self {
             FileDialogType::AskFilename => true,
             FileDialogType::AskDirectory => false,
-            FileDialogType::AskSaveAsFilename => true
+            FileDialogType::AskSaveAsFilename => true,
+            FileDialogType::AskMultipleFilenames => true
         }
     }
 
@@ -104,7 +222,8 @@ impl FileDialogType {
         match self {
             FileDialogType::AskFilename => false,
             FileDialogType::AskDirectory => true,
-            FileDialogType::AskSaveAsFilename => false
+            FileDialogType::AskSaveAsFilename => false,
+            FileDialogType::AskMultipleFilenames => false
         }
     }
 
@@ -112,9 +231,14 @@ impl FileDialogType {
         match self {
             FileDialogType::AskFilename => true,
             FileDialogType::AskDirectory => false,
-            FileDialogType::AskSaveAsFilename => true
+            FileDialogType::AskSaveAsFilename => true,
+            FileDialogType::AskMultipleFilenames => true
         }
     }
+
+    pub(super) fn allow_multiple(&self) -> bool {
+        matches!(self, FileDialogType::AskMultipleFilenames)
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -126,9 +250,46 @@ impl FileDialogType {
 pub enum FileExplorerEvent {
     Selecting,
     Selected(PathBuf),
+    SelectedMany(Vec<PathBuf>),
     Cancelled
 }
 
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Popups
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub(super) enum FileExplorerPopup<T: TypingBehaviour> {
+    FileName(TextInputState<T>),
+    NewDirectory(TextInputState<T>),
+    // Original path and the input for the new name
+    Rename(PathBuf, TextInputState<T>),
+    // Whether the path is a directory, which is deleted with all of its contents
+    ConfirmDelete(PathBuf, bool),
+    // The input of the filename popup is kept, to return to it when cancelled
+    ConfirmOverwrite(PathBuf, Option<TextInputState<T>>)
+}
+
+impl<T: TypingBehaviour> FileExplorerPopup<T> {
+    pub(super) fn input(&self) -> Option<&TextInputState<T>> {
+        match self {
+            FileExplorerPopup::FileName(input) => Some(input),
+            FileExplorerPopup::NewDirectory(input) => Some(input),
+            FileExplorerPopup::Rename(_, input) => Some(input),
+            FileExplorerPopup::ConfirmDelete(..) | FileExplorerPopup::ConfirmOverwrite(..) => None
+        }
+    }
+
+    pub(super) fn input_mut(&mut self) -> Option<&mut TextInputState<T>> {
+        match self {
+            FileExplorerPopup::FileName(input) => Some(input),
+            FileExplorerPopup::NewDirectory(input) => Some(input),
+            FileExplorerPopup::Rename(_, input) => Some(input),
+            FileExplorerPopup::ConfirmDelete(..) | FileExplorerPopup::ConfirmOverwrite(..) => None
+        }
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 //////////////////////////////////////////////////////////////////////////////////////////////////
 // File Explorer State
@@ -136,21 +297,139 @@ pub enum FileExplorerEvent {
 
 pub struct FileExplorerState<T: TypingBehaviour> {
     pub(super) table_state: TableState,
+    pub(super) provider: Arc<dyn FileSystemProvider>,
     pub(super) directory: PathBuf,
-    pub(super) files: Vec<FileInformation>,
+    // All entries in the current directory
+    pub(super) entries: Vec<FileInformation>,
+    // Background scan of the directory being loaded
+    pub(super) loader: Option<DirectoryLoader>,
+    load_notifier: Option<LoadNotifier>,
+    // Entries currently shown in the table
+    pub(super) files: Vec<FileRow>,
+    // Number of rows visible in the table at the last render
+    pub(super) page_size: usize,
+    pub(super) letter_jump: bool,
+    pub(super) tree_mode: bool,
+    pub(super) expanded: BTreeSet<PathBuf>,
+    // Lazily read contents of expanded directories
+    children: HashMap<PathBuf, Vec<FileInformation>>,
     pub(super) io_error: Option<String>,
-    pub(super) filename_input_state: Option<TextInputState<T>>,
-    pub(super) dialog_type: FileDialogType
+    pub(super) popup: Option<FileExplorerPopup<T>>,
+    pub(super) operations: FileOperations,
+    clipboard: Option<Clipboard>,
+    pub(super) dialog_type: FileDialogType,
+    // Checks applied to the name entered in a save-as dialog
+    confirm_overwrite: bool,
+    validate_filename: bool,
+    default_extension: Option<String>,
+    // Prefill of the filename popup, when started at a file
+    initial_filename: Option<String>,
+    pub(super) show_hidden: bool,
+    pub(super) filters: Vec<FileFilter>,
+    pub(super) active_filter: Option<usize>,
+    pub(super) search_input_state: Option<TextInputState<T>>,
+    pub(super) search_focused: bool,
+    pub(super) path_input_state: Option<TextInputState<T>>,
+    pub(super) sort_order: SortOrder,
+    pub(super) marked: BTreeSet<PathBuf>,
+    pub(super) sidebar: Option<Sidebar>,
+    pub(super) sidebar_focused: bool,
+    // Preview of the selected entry, together with the size limit used to load it
+    preview: Option<(usize, Preview)>,
+    #[cfg(feature = "watch")]
+    watcher: Option<DirectoryWatcher>,
+    // Changes reported while a directory is being read
+    #[cfg(feature = "watch")]
+    pending_watch: Option<WatchChange>
 }
 
 
 impl<T: TypingBehaviour> FileExplorerState<T> {
+    /// Open the given directory. Directories are read on a background thread;
+    /// the constructor (and every change of directory) waits briefly for the
+    /// listing, so that it is complete unless the directory is very large.
+    /// Use `is_loading` and `with_load_notifier` to show the rest of a large
+    /// directory as it is read.
     pub fn new(directory: PathBuf, dialog_type: FileDialogType) -> anyhow::Result<Self> {
-        let (files, table_state, io_error) = match Self::fresh_state(&directory) {
-            Ok(res) => (res.0, res.1, None),
-            Err(e) => (Vec::new(), TableState::default(), Some(e.to_string()))
+        Self::with_provider(LocalFileSystem, directory, dialog_type)
+    }
+
+    /// Browse the given directory of an arbitrary file system,
+    /// such as an archive or an in-memory tree.
+    pub fn with_provider(provider: impl FileSystemProvider + 'static,
+                         directory: PathBuf,
+                         dialog_type: FileDialogType) -> anyhow::Result<Self> {
+        Self::create(Arc::new(provider), directory, None, dialog_type)
+    }
+
+    /// Open the directory containing the given file, with the file selected.
+    /// The file does not need to exist; its name is used to prefill
+    /// the filename popup of a save-as dialog.
+    pub fn at_file(path: PathBuf, dialog_type: FileDialogType) -> anyhow::Result<Self> {
+        let path = if path.is_relative() { std::env::current_dir()?.join(path) } else { path };
+        Self::with_provider_at_file(LocalFileSystem, path, dialog_type)
+    }
+
+    pub fn with_provider_at_file(provider: impl FileSystemProvider + 'static,
+                                 path: PathBuf,
+                                 dialog_type: FileDialogType) -> anyhow::Result<Self> {
+        let provider: Arc<dyn FileSystemProvider> = Arc::new(provider);
+        let (Some(directory), Some(name)) = (provider.parent(&path), path.file_name()) else {
+            anyhow::bail!("Not a path to a file: {}", path.display());
+        };
+        let select = directory.join(name);
+        let mut state = Self::create(provider, directory, Some(select), dialog_type)?;
+        state.initial_filename = Some(name.to_string_lossy().into_owned());
+        Ok(state)
+    }
+
+    fn create(provider: Arc<dyn FileSystemProvider>,
+              directory: PathBuf,
+              select: Option<PathBuf>,
+              dialog_type: FileDialogType) -> anyhow::Result<Self> {
+        let mut loader = DirectoryLoader::spawn(provider.clone(), directory.clone(), select, None);
+        loader.started = true;
+        let mut state = Self {
+            table_state: TableState::default(),
+            provider,
+            directory,
+            entries: Vec::new(),
+            loader: Some(loader),
+            load_notifier: None,
+            files: Vec::new(),
+            page_size: 1,
+            letter_jump: false,
+            tree_mode: false,
+            expanded: BTreeSet::new(),
+            children: HashMap::new(),
+            io_error: None,
+            popup: None,
+            operations: FileOperations::default(),
+            clipboard: None,
+            dialog_type,
+            confirm_overwrite: true,
+            validate_filename: true,
+            default_extension: None,
+            initial_filename: None,
+            show_hidden: false,
+            filters: Vec::new(),
+            active_filter: None,
+            search_input_state: None,
+            search_focused: false,
+            path_input_state: None,
+            sort_order: SortOrder::default(),
+            marked: BTreeSet::new(),
+            sidebar: None,
+            sidebar_focused: false,
+            preview: None,
+            #[cfg(feature = "watch")]
+            watcher: None,
+            #[cfg(feature = "watch")]
+            pending_watch: None
         };
-        Ok(Self { table_state, directory, files, io_error, filename_input_state: None, dialog_type })
+        state.refresh_view(None);
+        state.wait_for_load(LOAD_WAIT);
+        Ok(state)
     }
 
     pub fn cwd(file_dialog_type: FileDialogType) -> anyhow::Result<Self> {
@@ -158,56 +437,453 @@ impl<T: TypingBehaviour> FileExplorerState<T> {
         Self::new(directory, file_dialog_type)
     }
 
+    pub fn with_hidden_files(mut self, show_hidden: bool) -> Self {
+        self.set_show_hidden(show_hidden);
+        self
+    }
+
+    /// Set the filters which can be cycled through. The first filter
+    /// becomes active; cycling past the last filter shows all files.
+    pub fn with_filters(mut self, filters: Vec<FileFilter>) -> Self {
+        self.set_filters(filters);
+        self
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        self.show_hidden = show_hidden;
+        self.refresh_view(self.selected_path());
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    pub fn set_filters(&mut self, filters: Vec<FileFilter>) {
+        self.active_filter = if filters.is_empty() { None } else { Some(0) };
+        self.filters = filters;
+        self.refresh_view(self.selected_path());
+    }
+
+    pub fn active_filter(&self) -> Option<&FileFilter> {
+        self.active_filter.map(|index| &self.filters[index])
+    }
+
+    /// Set the file operations the user is allowed to perform.
+    pub fn with_operations(mut self, operations: FileOperations) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    pub fn operations(&self) -> FileOperations {
+        self.operations
+    }
+
+    /// Ask for confirmation before selecting an existing file in a save-as dialog.
+    pub fn with_overwrite_confirmation(mut self, confirm_overwrite: bool) -> Self {
+        self.confirm_overwrite = confirm_overwrite;
+        self
+    }
+
+    /// Reject file names entered in a save-as dialog which are empty or contain a path separator.
+    pub fn with_filename_validation(mut self, validate_filename: bool) -> Self {
+        self.validate_filename = validate_filename;
+        self
+    }
+
+    /// Extension appended to file names entered in a save-as dialog which have none, e.g. `"txt"`.
+    pub fn with_default_extension(mut self, extension: Option<&str>) -> Self {
+        self.default_extension = extension
+            .map(|extension| extension.trim_start_matches('.').to_string())
+            .filter(|extension| !extension.is_empty());
+        self
+    }
+
+    pub fn default_extension(&self) -> Option<&str> {
+        self.default_extension.as_deref()
+    }
+
+    pub fn with_sidebar(mut self, mut sidebar: Sidebar) -> Self {
+        sidebar.visit(&self.directory);
+        self.sidebar = Some(sidebar);
+        self
+    }
+
+    pub fn sidebar(&self) -> Option<&Sidebar> {
+        self.sidebar.as_ref()
+    }
+
+    /// Watch the current directory for changes made by other processes
+    /// and update the listing as they happen. Only meaningful when
+    /// browsing the local file system.
+    #[cfg(feature = "watch")]
+    pub fn with_watcher(mut self, enabled: bool) -> anyhow::Result<Self> {
+        self.watcher = if enabled { Some(DirectoryWatcher::new(&self.directory, self.load_notifier.clone())?) } else { None };
+        Ok(self)
+    }
+
+    pub fn with_sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.set_sort_order(sort_order);
+        self
+    }
+
+    pub fn set_sort_order(&mut self, sort_order: SortOrder) {
+        self.sort_order = sort_order;
+        self.sort_entries();
+        self.refresh_view(self.selected_path());
+    }
+
+    pub fn sort_order(&self) -> SortOrder {
+        self.sort_order
+    }
+
+    pub fn marked(&self) -> impl Iterator<Item=&Path> {
+        self.marked.iter().map(|path| path.as_path())
+    }
+
+    pub(super) fn is_marked(&self, row: &FileRow) -> bool {
+        self.marked.contains(&row.path)
+    }
+
+    /// Jump to the next entry starting with a typed character, rather than
+    /// starting a search. Searching remains available through `/`.
+    pub fn with_letter_jump(mut self, letter_jump: bool) -> Self {
+        self.letter_jump = letter_jump;
+        self
+    }
+
+    pub fn letter_jump(&self) -> bool {
+        self.letter_jump
+    }
+
+    /// Show expanded directories in place, below their parent.
+    pub fn with_tree_mode(mut self, tree_mode: bool) -> Self {
+        self.set_tree_mode(tree_mode);
+        self
+    }
+
+    pub fn set_tree_mode(&mut self, tree_mode: bool) {
+        self.tree_mode = tree_mode;
+        self.refresh_view(self.selected_path());
+    }
+
+    pub fn tree_mode(&self) -> bool {
+        self.tree_mode
+    }
+
+    pub fn search_query(&self) -> Option<&str> {
+        self.search_input_state
+            .as_ref()
+            .map(|input| input.text())
+            .filter(|query| !query.is_empty())
+    }
+
+    /// Whether the current directory is still being read in the background.
+    pub fn is_loading(&self) -> bool {
+        self.loader.is_some()
+    }
+
+    /// Number of entries read so far while loading a directory.
+    pub fn loading_count(&self) -> Option<usize> {
+        self.loader.as_ref().map(|loader| loader.loaded)
+    }
+
+    /// Process the entries read in the background since the last call.
+    /// This also happens when handling events and rendering; applications
+    /// should call it (and redraw) periodically while `is_loading` is true.
+    /// Returns whether the state changed.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(message) = self.loader.as_ref().and_then(|loader| loader.receive(false)) {
+            self.handle_load_message(message);
+            changed = true;
+        }
+        if changed && self.loader.is_some() {
+            // Entries are shown in the order they are read,
+            // and sorted once the directory has been read completely
+            self.refresh_view(self.selected_path());
+        }
+        #[cfg(feature = "watch")]
+        {
+            changed |= self.apply_watch_changes();
+        }
+        changed
+    }
+
+    #[cfg(feature = "watch")]
+    fn apply_watch_changes(&mut self) -> bool {
+        if let Some(change) = self.watcher.as_ref().and_then(|watcher| watcher.changes()) {
+            self.pending_watch = Some(match self.pending_watch.take() {
+                Some(pending) => pending.merge(change),
+                None => change
+            });
+        }
+        if self.loader.is_some() {
+            // The scan may have read an entry before it changed,
+            // so the changes are applied once it has finished
+            return false;
+        }
+        let Some(change) = self.pending_watch.take() else {
+            return false;
+        };
+        match change {
+            WatchChange::Rescan => self.reload_directory(None),
+            WatchChange::Entries(paths) => {
+                for path in paths {
+                    let Some(name) = path.file_name().map(OsStr::to_os_string) else {
+                        continue;
+                    };
+                    self.children.remove(&path);
+                    self.entries.retain(|info| info.name != name);
+                    if let Ok(info) = self.provider.metadata(&path) {
+                        self.entries.push(info);
+                    }
+                    if self.preview.as_ref().is_some_and(|(_, preview)| preview.path == path) {
+                        self.preview = None;
+                    }
+                }
+                self.sort_entries();
+                self.refresh_view(self.selected_path());
+            }
+        }
+        true
+    }
+
+    /// Call the given function from a background thread whenever entries have been
+    /// read, or the watcher has seen a change to the directory, e.g. to wake up an
+    /// event loop which blocks on user input. The application should then call
+    /// `poll` (or handle an event) and redraw.
+    pub fn with_load_notifier(mut self, notify: impl Fn() + Send + Sync + 'static) -> Self {
+        self.load_notifier = Some(Arc::new(notify));
+        #[cfg(feature = "watch")]
+        if let Some(watcher) = self.watcher.as_ref() {
+            watcher.set_notifier(self.load_notifier.clone());
+        }
+        // Restart a running scan, so that it uses the notifier as well
+        if let Some(mut loader) = self.loader.take() {
+            let select = loader.select.take();
+            self.load_directory(loader.path().to_path_buf(), select);
+        }
+        self
+    }
+
+    // Process messages until the directory has been read or the timeout expires,
+    // so small directories are shown completely as soon as they are opened.
+    fn wait_for_load(&mut self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        let mut changed = false;
+        while let Some(message) = self.loader.as_ref().and_then(|loader| loader.receive_until(deadline)) {
+            self.handle_load_message(message);
+            changed = true;
+        }
+        if changed && self.loader.is_some() {
+            self.refresh_view(self.selected_path());
+        }
+    }
+
+    /// Block until the current directory has been read completely.
+    pub fn wait_until_loaded(&mut self) {
+        while let Some(message) = self.loader.as_ref().and_then(|loader| loader.receive(true)) {
+            self.handle_load_message(message);
+        }
+    }
+
     pub(super) fn update_directory(&mut self, path: PathBuf) -> anyhow::Result<()> {
-        match Self::fresh_state(&path) {
-            Ok((files, table_state)) => {
-                self.directory = path;
-                self.files = files;
-                self.table_state = table_state;
-                //self.io_error = None;
-                Ok(())
+        self.load_directory(path, None);
+        Ok(())
+    }
+
+    // Start reading the directory. The current listing stays in place until the
+    // first entries arrive, so a failing directory does not replace it.
+    fn load_directory(&mut self, path: PathBuf, select: Option<PathBuf>) {
+        // Dropping a previous loader cancels its scan
+        self.loader = Some(DirectoryLoader::spawn(
+            self.provider.clone(), path, select, self.load_notifier.clone()
+        ));
+        self.wait_for_load(LOAD_WAIT);
+    }
+
+    fn handle_load_message(&mut self, message: LoadMessage) {
+        let Some(loader) = self.loader.as_mut() else {
+            return;
+        };
+        if !loader.started && !matches!(message, LoadMessage::Failed(_)) {
+            loader.started = true;
+            let path = loader.path().to_path_buf();
+            self.entries.clear();
+            self.children.clear();
+            self.preview = None;
+            if path != self.directory {
+                self.enter_directory(path);
             }
-            Err(e) => {
+        }
+        match message {
+            LoadMessage::Batch(batch) => {
+                if let Some(loader) = self.loader.as_mut() {
+                    loader.loaded += batch.len();
+                }
+                self.entries.extend(batch);
+            }
+            LoadMessage::Done => {
+                let select = self.loader.take().and_then(|mut loader| loader.select.take());
+                self.sort_entries();
+                self.refresh_view(select.or_else(|| self.selected_path()));
+            }
+            LoadMessage::Failed(e) => {
+                self.loader = None;
+                self.io_error = Some(e);
+                self.sort_entries();
+                self.refresh_view(self.selected_path());
+            }
+        }
+    }
+
+    fn enter_directory(&mut self, path: PathBuf) {
+        if let Some(sidebar) = self.sidebar.as_mut() {
+            sidebar.visit(&path);
+        }
+        #[cfg(feature = "watch")]
+        if let Some(watcher) = self.watcher.as_mut() {
+            if let Err(e) = watcher.set_path(&path) {
                 self.io_error = Some(e.to_string());
-                Ok(())
             }
+            // Changes to the previous directory no longer apply
+            self.pending_watch = None;
         }
+        self.directory = path;
+        self.search_input_state = None;
+        self.search_focused = false;
+        self.table_state = TableState::default();
     }
     
-    fn fresh_state(path: &Path) -> anyhow::Result<(Vec<FileInformation>, TableState)> {
-        let mut files = Self::collect_files(path)?;
-        files.sort();
-        let table_state = TableState::new()
-            .with_selected(0)
-            .with_offset(0);
-        Ok((files, table_state))
-    }
-
-    fn collect_files(path: &Path) -> anyhow::Result<Vec<FileInformation>> {
-        let mut files = Vec::new();
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            let metadata = entry.metadata()?;
-            let file_type = if metadata.is_file() {
-                FileType::File
-            } else if metadata.is_dir() {
-                FileType::Directory
-            } else {
-                assert!(metadata.is_symlink());
-                let symlink_metadata = fs::metadata(entry.path())?;
-                if symlink_metadata.is_file() {
-                    FileType::FileSymlink
-                } else {
-                    assert!(symlink_metadata.is_dir());
-                    FileType::DirectorySymlink
-                }
-            };
-            let name = entry.file_name()
-                .into_string()
-                .expect("Failed to convert filename");
-            files.push(FileInformation { file_type, name });
+    fn sort_entries(&mut self) {
+        let order = self.sort_order;
+        order.sort(&mut self.entries);
+        for children in self.children.values_mut() {
+            order.sort(children);
+        }
+    }
+
+    // Recompute the visible entries, keeping the selection on the
+    // entry with the given path if it is still visible.
+    pub(super) fn refresh_view(&mut self, keep_selected: Option<PathBuf>) {
+        let entries = std::mem::take(&mut self.entries);
+        let mut rows = Vec::new();
+        self.collect_rows(&mut rows, &self.directory.clone(), &entries, &mut Vec::new());
+        self.entries = entries;
+        self.files = rows;
+        let fallback = self.table_state.selected()
+            .unwrap_or(0)
+            .min(self.files.len().saturating_sub(1));
+        if !keep_selected.is_some_and(|path| self.select_by_path(&path)) {
+            self.table_state.select(if self.files.is_empty() { None } else { Some(fallback) });
+        }
+    }
+
+    fn collect_rows(&mut self,
+                    rows: &mut Vec<FileRow>,
+                    parent: &Path,
+                    entries: &[FileInformation],
+                    guides: &mut Vec<bool>) {
+        let visible = entries.iter()
+            .filter(|info| self.is_visible(info))
+            .collect::<Vec<_>>();
+        for (index, info) in visible.iter().enumerate() {
+            let path = parent.join(&info.name);
+            let last = index + 1 == visible.len();
+            let expanded = self.tree_mode && info.file_type.is_dir() && self.expanded.contains(&path);
+            rows.push(FileRow {
+                info: (*info).clone(),
+                path: path.clone(),
+                depth: guides.len(),
+                guides: guides.clone(),
+                last,
+                expanded
+            });
+            if expanded {
+                let children = self.children_of(&path);
+                guides.push(!last);
+                self.collect_rows(rows, &path, &children, guides);
+                guides.pop();
+            }
+        }
+    }
+
+    // Entries of an expanded directory, read on first use
+    fn children_of(&mut self, path: &Path) -> Vec<FileInformation> {
+        if let Some(children) = self.children.get(path) {
+            return children.clone();
+        }
+        match self.provider.read_dir(path) {
+            Ok(mut children) => {
+                self.sort_order.sort(&mut children);
+                self.children.insert(path.to_path_buf(), children.clone());
+                children
+            }
+            Err(e) => {
+                self.io_error = Some(e.to_string());
+                self.expanded.remove(path);
+                Vec::new()
+            }
+        }
+    }
+
+    fn is_visible(&self, info: &FileInformation) -> bool {
+        if info.is_hidden() && !self.show_hidden {
+            return false;
+        }
+        if self.search_query().is_some_and(|query| fuzzy_match(query, &info.display_name()).is_none()) {
+            return false;
+        }
+        if !info.file_type.is_dir() {
+            self.dialog_type.show_filenames() && 
+                self.active_filter().is_none_or(|filter| filter.matches(&info.display_name()))
+        } else {
+            true
+        }
+    }
+
+    pub(super) fn selected_file(&self) -> Option<&FileRow> {
+        self.table_state.selected().and_then(|index| self.files.get(index))
+    }
+
+    // The rows the file list will show, scrolled as the table does to keep the selection visible
+    pub(super) fn visible_rows(&self) -> &[FileRow] {
+        let height = self.page_size.max(1);
+        let mut offset = self.table_state.offset();
+        if let Some(selected) = self.table_state.selected() {
+            offset = offset.min(selected).max((selected + 1).saturating_sub(height));
+        }
+        let start = offset.min(self.files.len());
+        &self.files[start..(start + height).min(self.files.len())]
+    }
+
+    // Load the preview of the selected entry, unless it is already cached
+    pub(super) fn preview(&mut self, max_bytes: usize) -> Option<&Preview> {
+        let row = self.selected_file()?;
+        let cached = self.preview
+            .as_ref()
+            .is_some_and(|(limit, preview)| *limit == max_bytes && preview.path == row.path);
+        if !cached {
+            let preview = Preview::load(
+                self.provider.as_ref(), &row.path, &row.info, self.sort_order, max_bytes
+            );
+            self.preview = Some((max_bytes, preview));
+        }
+        self.preview.as_ref().map(|(_, preview)| preview)
+    }
+
+    fn selected_path(&self) -> Option<PathBuf> {
+        self.selected_file().map(|row| row.path.clone())
+    }
+
+    fn select_by_path(&mut self, path: &Path) -> bool {
+        match self.files.iter().position(|row| row.path == path) {
+            Some(index) => {
+                self.table_state.select(Some(index));
+                true
+            }
+            None => false
         }
-        Ok(files)
     }
 
     // pub fn handle_events(&mut self) -> anyhow::Result<FileExplorerEvent> {
@@ -215,58 +891,537 @@ impl<T: TypingBehaviour> FileExplorerState<T> {
     // }
 
     fn move_arrow_up(&mut self) -> anyhow::Result<FileExplorerEvent> {
-        let sel = self.table_state.selected().unwrap();
-        if sel > 0 {
-            self.table_state.select(Some(sel - 1));
+        if let Some(sel) = self.table_state.selected() {
+            if sel > 0 {
+                self.table_state.select(Some(sel - 1));
+            }
         }
         Ok(FileExplorerEvent::Selecting)
     }
 
     fn move_arrow_down(&mut self) -> anyhow::Result<FileExplorerEvent> {
-        let sel =  self.table_state.selected().unwrap();
-        if sel + 1 < self.files.len() {
-            self.table_state.select(Some(sel + 1));
+        if let Some(sel) = self.table_state.selected() {
+            if sel + 1 < self.files.len() {
+                self.table_state.select(Some(sel + 1));
+            }
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn move_page(&mut self, offset: isize) -> anyhow::Result<FileExplorerEvent> {
+        if let Some(sel) = self.table_state.selected() {
+            let next = sel.saturating_add_signed(offset * self.page_size.max(1) as isize);
+            self.table_state.select(Some(next.min(self.files.len().saturating_sub(1))));
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn move_to_start(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if !self.files.is_empty() {
+            self.table_state.select(Some(0));
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn move_to_end(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if !self.files.is_empty() {
+            self.table_state.select(Some(self.files.len() - 1));
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    // Select the next entry starting with the character, wrapping around,
+    // so that repeated presses cycle through all matching entries.
+    fn jump_to_letter(&mut self, letter: char) -> anyhow::Result<FileExplorerEvent> {
+        let count = self.files.len();
+        let start = self.table_state.selected().map_or(0, |sel| sel + 1);
+        let next = (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|&index| {
+                self.files[index].info
+                    .display_name()
+                    .chars()
+                    .next()
+                    .is_some_and(|first| first.to_lowercase().eq(letter.to_lowercase()))
+            });
+        if next.is_some() {
+            self.table_state.select(next);
         }
         Ok(FileExplorerEvent::Selecting)
     }
 
     fn move_directory_up(&mut self) -> anyhow::Result<FileExplorerEvent> {
-        if let Some(parent) = self.directory.parent() {
-            let old = self.directory.file_name()
-                .expect("Failed to get filename")
-                .to_os_string()
-                .into_string()
-                .expect("Failed to convert filename");
-            self.update_directory(parent.to_path_buf())?;
-            let index = self.files.iter().enumerate()
-                .find(|(_, info)| info.name == old)
-                .expect("Failed to find file index");
-            self.table_state.select(Some(index.0));
+        if self.tree_mode {
+            if let Some(row) = self.selected_file() {
+                if row.expanded {
+                    let path = row.path.clone();
+                    return self.collapse(path);
+                }
+                if row.depth > 0 {
+                    let parent = row.path.parent().map(Path::to_path_buf);
+                    if let Some(parent) = parent {
+                        self.select_by_path(&parent);
+                    }
+                    return Ok(FileExplorerEvent::Selecting);
+                }
+            }
+        }
+        if let Some(parent) = self.provider.parent(&self.directory) {
+            let old = self.directory.clone();
+            self.load_directory(parent, Some(old));
         }
         Ok(FileExplorerEvent::Selecting)
     }
 
     fn move_directory_down(&mut self) -> anyhow::Result<FileExplorerEvent> {
-        let sel =  self.table_state.selected().unwrap();
-        let info = self.files.get(sel).unwrap();
-        if info.file_type == FileType::Directory {
-            let path = self.directory.join(info.name.clone());
+        let Some(row) = self.selected_file() else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        if row.info.file_type == FileType::Directory {
+            let path = row.path.clone();
+            if !self.tree_mode {
+                self.update_directory(path)?;
+            } else if row.expanded {
+                return self.move_arrow_down();
+            } else {
+                return self.expand(path);
+            }
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn expand(&mut self, path: PathBuf) -> anyhow::Result<FileExplorerEvent> {
+        self.expanded.insert(path.clone());
+        self.refresh_view(Some(path));
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn collapse(&mut self, path: PathBuf) -> anyhow::Result<FileExplorerEvent> {
+        self.expanded.remove(&path);
+        self.refresh_view(Some(path));
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn toggle_tree_mode(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        self.set_tree_mode(!self.tree_mode);
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn switch_focus(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        self.sidebar_focused = self.sidebar.is_some() && !self.sidebar_focused;
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn move_sidebar(&mut self, offset: isize) -> anyhow::Result<FileExplorerEvent> {
+        if let Some(sidebar) = self.sidebar.as_mut() {
+            sidebar.move_selection(offset);
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn open_sidebar_entry(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if let Some(path) = self.sidebar.as_ref().and_then(|sidebar| sidebar.selected_path()) {
+            // The directory is read in the background, so a failure
+            // is only reported once the list already has focus
             self.update_directory(path)?;
+            self.sidebar_focused = false;
         }
         Ok(FileExplorerEvent::Selecting)
     }
 
-    fn get_selected_file(&mut self) -> anyhow::Result<FileExplorerEvent> {
-        let sel =  self.table_state.selected().unwrap();
-        let info = self.files.get(sel).unwrap();
+    fn toggle_bookmark(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if let Some(sidebar) = self.sidebar.as_mut() {
+            if let Err(e) = sidebar.toggle_bookmark(&self.directory) {
+                self.io_error = Some(e.to_string());
+            }
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn toggle_hidden(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        self.set_show_hidden(!self.show_hidden);
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn cycle_filter(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if !self.filters.is_empty() {
+            self.active_filter = match self.active_filter {
+                None => Some(0),
+                Some(index) if index + 1 < self.filters.len() => Some(index + 1),
+                Some(_) => None
+            };
+            self.refresh_view(self.selected_path());
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn cycle_sort_mode(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let key = self.sort_order.key().next();
+        self.set_sort_order(self.sort_order.with_key(key));
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn toggle_sort_direction(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let direction = self.sort_order.direction().reversed();
+        self.set_sort_order(self.sort_order.with_direction(direction));
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn start_search(&mut self, initial: Option<char>) -> anyhow::Result<FileExplorerEvent> {
+        let input = self.search_input_state.get_or_insert_with(TextInputState::default);
+        if let Some(c) = initial {
+            let _ = input.handle_native_event(Some(TextInputAction::Other(UserAction::Typing(c))));
+        }
+        self.search_focused = true;
+        self.refresh_view(self.selected_path());
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn handle_search_input(&mut self, action: TextInputAction) -> anyhow::Result<FileExplorerEvent> {
+        let Some(input) = self.search_input_state.as_mut() else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        match input.handle_native_event(Some(action)) {
+            Ok(TextInputEvent::Cancelled) => {
+                self.search_input_state = None;
+                self.search_focused = false;
+            }
+            Ok(TextInputEvent::Submitted(query)) => {
+                // Keep the filter, but give the focus back to the file list
+                self.search_focused = false;
+                if query.is_empty() {
+                    self.search_input_state = None;
+                }
+            }
+            Ok(TextInputEvent::Typing) => (),
+            Err(e) => self.io_error = Some(e.to_string())
+        }
+        self.refresh_view(self.selected_path());
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn start_path_edit(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let mut input = TextInputState::default();
+        input.set_text(self.directory.display().to_string());
+        self.path_input_state = Some(input);
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn handle_path_input(&mut self, action: TextInputAction) -> anyhow::Result<FileExplorerEvent> {
+        let Some(input) = self.path_input_state.as_mut() else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        let text = match input.handle_native_event(Some(action)) {
+            Ok(TextInputEvent::Submitted(text)) => text,
+            Ok(TextInputEvent::Cancelled) => {
+                self.path_input_state = None;
+                return Ok(FileExplorerEvent::Selecting);
+            }
+            Ok(TextInputEvent::Typing) => return Ok(FileExplorerEvent::Selecting),
+            Err(e) => {
+                // The clipboard is not available
+                self.io_error = Some(e.to_string());
+                return Ok(FileExplorerEvent::Selecting);
+            }
+        };
+        self.path_input_state = None;
+        let path = self.resolve_path(text.trim());
+        match self.provider.metadata(&path) {
+            Ok(info) if info.file_type.is_file() => {
+                // Open the containing directory with the file selected
+                if let Some(parent) = self.provider.parent(&path) {
+                    self.load_directory(parent, Some(path));
+                }
+            }
+            Ok(_) => self.update_directory(path)?,
+            Err(e) => self.io_error = Some(e.to_string())
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    // Expand `~` and make the path absolute relative to the current directory.
+    fn resolve_path(&self, text: &str) -> PathBuf {
+        let expanded = match text.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with(std::path::is_separator) => {
+                match home_directory() {
+                    Some(home) => home.join(rest.trim_start_matches(std::path::is_separator)),
+                    None => PathBuf::from(text)
+                }
+            }
+            _ => PathBuf::from(text)
+        };
+        let mut path = self.directory.clone();
+        for component in expanded.components() {
+            match component {
+                Component::Prefix(_) | Component::RootDir => path.push(component),
+                Component::CurDir => (),
+                Component::ParentDir => {
+                    path.pop();
+                }
+                Component::Normal(name) => path.push(name)
+            }
+        }
+        path
+    }
+
+    fn is_selectable(&self, info: &FileInformation) -> bool {
         if info.file_type.is_dir() && !self.dialog_type.allow_selecting_directory() {
-            return Ok(FileExplorerEvent::Selecting)
+            return false;
         }
         if info.file_type.is_file() && !self.dialog_type.allow_selecting_files() {
+            return false;
+        }
+        true
+    }
+
+    fn toggle_mark(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if let Some(row) = self.selected_file().filter(|row| self.is_selectable(&row.info)) {
+            let path = row.path.clone();
+            if !self.marked.remove(&path) {
+                self.marked.insert(path);
+            }
+        }
+        self.move_arrow_down()
+    }
+
+    fn mark_all(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let paths = self.files.iter()
+            .filter(|row| self.is_selectable(&row.info))
+            .map(|row| row.path.clone())
+            .collect::<Vec<_>>();
+        self.marked.extend(paths);
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn invert_marks(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let paths = self.files.iter()
+            .filter(|row| self.is_selectable(&row.info))
+            .map(|row| row.path.clone())
+            .collect::<Vec<_>>();
+        for path in paths {
+            if !self.marked.remove(&path) {
+                self.marked.insert(path);
+            }
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn reload_directory(&mut self, select: Option<PathBuf>) {
+        let select = select.or_else(|| self.selected_path());
+        self.load_directory(self.directory.clone(), select);
+    }
+
+    fn open_popup(&mut self, popup: FileExplorerPopup<T>) -> anyhow::Result<FileExplorerEvent> {
+        // Setting the state will also update the ui
+        self.popup = Some(popup);
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn start_file_name(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let mut input = TextInputState::default();
+        if let Some(ref name) = self.initial_filename {
+            input.set_text(name.clone());
+        }
+        self.open_popup(FileExplorerPopup::FileName(input))
+    }
+
+    fn start_create_directory(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if !self.operations.create_directory() {
+            return Ok(FileExplorerEvent::Selecting);
+        }
+        self.open_popup(FileExplorerPopup::NewDirectory(TextInputState::default()))
+    }
+
+    fn start_rename(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let Some(row) = self.selected_file().filter(|_| self.operations.rename()) else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        let mut input = TextInputState::default();
+        input.set_text(row.info.display_name().into_owned());
+        self.open_popup(FileExplorerPopup::Rename(row.path.clone(), input))
+    }
+
+    fn start_delete(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let Some(row) = self.selected_file().filter(|_| self.operations.delete()) else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        let is_dir = row.info.file_type == FileType::Directory;
+        self.open_popup(FileExplorerPopup::ConfirmDelete(row.path.clone(), is_dir))
+    }
+
+    fn copy_path(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if !self.operations.copy_path() {
+            return Ok(FileExplorerEvent::Selecting);
+        }
+        let path = self.selected_path().unwrap_or_else(|| self.directory.clone());
+        // The clipboard is kept alive, since some platforms
+        // drop the contents together with the clipboard.
+        let result = match self.clipboard {
+            Some(ref mut clipboard) => clipboard.set_text(path.display().to_string()),
+            None => Clipboard::new().and_then(|clipboard| {
+                self.clipboard.insert(clipboard).set_text(path.display().to_string())
+            })
+        };
+        if let Err(e) = result {
+            self.io_error = Some(e.to_string());
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn validate_name(name: &str) -> anyhow::Result<()> {
+        if name.is_empty() || name == "." || name == ".." {
+            anyhow::bail!("Invalid name: '{}'", name)
+        }
+        if name.contains(std::path::is_separator) {
+            anyhow::bail!("Name must not contain a path separator: '{}'", name)
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, action: TextInputAction) -> anyhow::Result<FileExplorerEvent> {
+        let Some(input) = self.popup.as_mut().and_then(|popup| popup.input_mut()) else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        let name = match input.handle_native_event(Some(action)) {
+            Ok(TextInputEvent::Submitted(name)) => name,
+            Ok(TextInputEvent::Cancelled) => {
+                self.popup = None;
+                return Ok(FileExplorerEvent::Selecting);
+            }
+            Ok(TextInputEvent::Typing) => return Ok(FileExplorerEvent::Selecting),
+            Err(e) => {
+                // The clipboard is not available
+                self.io_error = Some(e.to_string());
+                return Ok(FileExplorerEvent::Selecting);
+            }
+        };
+        let target = self.directory.join(&name);
+        let result = match self.popup.take().expect("No popup") {
+            FileExplorerPopup::FileName(_) if self.dialog_type != FileDialogType::AskSaveAsFilename => {
+                return Ok(FileExplorerEvent::Selected(target));
+            }
+            FileExplorerPopup::FileName(input) => {
+                let checked = self.check_filename(&name);
+                return match checked {
+                    Ok(target) => self.save_as(target, Some(input)),
+                    Err(e) => {
+                        // Keep the popup open so the name can be corrected
+                        self.io_error = Some(e.to_string());
+                        self.open_popup(FileExplorerPopup::FileName(input))
+                    }
+                };
+            }
+            FileExplorerPopup::ConfirmDelete(..) | FileExplorerPopup::ConfirmOverwrite(..) => {
+                return Ok(FileExplorerEvent::Selecting);
+            }
+            FileExplorerPopup::NewDirectory(_) => Self::validate_name(&name)
+                .and_then(|_| self.provider.create_dir(&target))
+                .map(|_| target),
+            FileExplorerPopup::Rename(source, _) => {
+                // Rename within the parent, which differs from the current directory in tree mode
+                // Names which are not valid UTF-8 are shown lossily, so compare the shown name
+                let unchanged = source.file_name()
+                    .is_some_and(|original| original.to_string_lossy() == name);
+                if unchanged {
+                    return Ok(FileExplorerEvent::Selecting);
+                }
+                let target = source.with_file_name(&name);
+                Self::validate_name(&name)
+                    .and_then(|_| self.provider.rename(&source, &target))
+                    .map(|_| {
+                        if self.marked.remove(&source) {
+                            self.marked.insert(target.clone());
+                        }
+                        target
+                    })
+            }
+        };
+        match result {
+            Ok(target) => self.reload_directory(Some(target)),
+            Err(e) => self.io_error = Some(e.to_string())
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    // Validate the name entered in a save-as dialog and add the default extension
+    fn check_filename(&self, name: &str) -> anyhow::Result<PathBuf> {
+        if self.validate_filename {
+            Self::validate_name(name)?;
+        }
+        let mut target = self.directory.join(name);
+        if let Some(ref extension) = self.default_extension {
+            if target.extension().is_none() {
+                target.as_mut_os_string().push(format!(".{}", extension));
+            }
+        }
+        Ok(target)
+    }
+
+    // Select the target of a save-as dialog, asking before replacing an existing file.
+    // The input is that of the filename popup, if the name was typed.
+    fn save_as(&mut self,
+               target: PathBuf,
+               input: Option<TextInputState<T>>) -> anyhow::Result<FileExplorerEvent> {
+        match self.provider.metadata(&target) {
+            Ok(info) if info.file_type.is_dir() => {
+                self.io_error = Some(format!("{} is a directory", target.display()));
+                self.popup = input.map(FileExplorerPopup::FileName);
+                Ok(FileExplorerEvent::Selecting)
+            }
+            Ok(_) if self.confirm_overwrite => {
+                self.open_popup(FileExplorerPopup::ConfirmOverwrite(target, input))
+            }
+            _ => Ok(FileExplorerEvent::Selected(target))
+        }
+    }
+
+    fn cancel_popup(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        self.popup = match self.popup.take() {
+            // Return to the typed name, so that another one can be chosen
+            Some(FileExplorerPopup::ConfirmOverwrite(_, input)) => input.map(FileExplorerPopup::FileName),
+            _ => None
+        };
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn confirm_popup(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        let path = match self.popup.take() {
+            Some(FileExplorerPopup::ConfirmDelete(path, _)) => path,
+            Some(FileExplorerPopup::ConfirmOverwrite(path, _)) => return Ok(FileExplorerEvent::Selected(path)),
+            popup => {
+                self.popup = popup;
+                return Ok(FileExplorerEvent::Selecting);
+            }
+        };
+        match self.provider.remove(&path) {
+            Ok(()) => {
+                self.marked.retain(|marked| !marked.starts_with(&path));
+                self.reload_directory(None);
+            }
+            Err(e) => self.io_error = Some(e.to_string())
+        }
+        Ok(FileExplorerEvent::Selecting)
+    }
+
+    fn get_selected_file(&mut self) -> anyhow::Result<FileExplorerEvent> {
+        if self.dialog_type.allow_multiple() && !self.marked.is_empty() {
+            let paths = self.marked.iter().cloned().collect();
+            return Ok(FileExplorerEvent::SelectedMany(paths));
+        }
+        let Some(row) = self.selected_file() else {
+            return Ok(FileExplorerEvent::Selecting);
+        };
+        if !self.is_selectable(&row.info) {
             return Ok(FileExplorerEvent::Selecting)
         }
-        let path = self.directory.join(info.name.clone());
-        Ok(FileExplorerEvent::Selected(path))
+        let path = row.path.clone();
+        if self.dialog_type == FileDialogType::AskSaveAsFilename {
+            self.save_as(path, None)
+        } else if self.dialog_type.allow_multiple() {
+            Ok(FileExplorerEvent::SelectedMany(vec![path]))
+        } else {
+            Ok(FileExplorerEvent::Selected(path))
+        }
     }
 }
 
@@ -278,39 +1433,145 @@ impl<T: TypingBehaviour> RemyWidgetState for FileExplorerState<T> {
     fn handle_native_event(&mut self, event: Option<Self::Command>) -> Self::EventOutput {
         if let Some(command) = event {
             let _ = self.io_error.take();      // Reset error on key action
+            self.poll();
             match command {
                 FileExplorerCommand::ArrowUp => self.move_arrow_up(),
                 FileExplorerCommand::ArrowDown => self.move_arrow_down(),
+                FileExplorerCommand::PageUp => self.move_page(-1),
+                FileExplorerCommand::PageDown => self.move_page(1),
+                FileExplorerCommand::Home => self.move_to_start(),
+                FileExplorerCommand::End => self.move_to_end(),
+                FileExplorerCommand::JumpToLetter(letter) => self.jump_to_letter(letter),
                 FileExplorerCommand::DirectoryUp => self.move_directory_up(),
                 FileExplorerCommand::DirectoryDown => self.move_directory_down(),
                 FileExplorerCommand::Confirm => self.get_selected_file(),
-                FileExplorerCommand::EnterFileName => {
-                    // Setting the state will also update the ui 
-                    self.filename_input_state = Some(TextInputState::default());
+                FileExplorerCommand::Refresh => {
+                    self.reload_directory(None);
                     Ok(FileExplorerEvent::Selecting)
-                },
-                FileExplorerCommand::Exit => Ok(FileExplorerEvent::Cancelled),
-                FileExplorerCommand::FileNameDialogInput(inner) => {
-                    let state = self.filename_input_state
-                        .as_mut()
-                        .expect("No filename input state");
-                    let event = state.handle_native_event(Some(inner));
-                    match event {
-                        TextInputEvent::Submitted(filename) => {
-                            let full_path = self.directory.join(filename);
-                            Ok(FileExplorerEvent::Selected(full_path))
-                        }
-                        TextInputEvent::Cancelled => {
-                            self.filename_input_state = None;
-                            Ok(FileExplorerEvent::Selecting)
-                        }
-                        TextInputEvent::Typing => Ok(FileExplorerEvent::Selecting)
-                    }
-                    
                 }
+                FileExplorerCommand::ToggleHidden => self.toggle_hidden(),
+                FileExplorerCommand::SwitchFocus => self.switch_focus(),
+                FileExplorerCommand::SidebarUp => self.move_sidebar(-1),
+                FileExplorerCommand::SidebarDown => self.move_sidebar(1),
+                FileExplorerCommand::SidebarOpen => self.open_sidebar_entry(),
+                FileExplorerCommand::ToggleBookmark => self.toggle_bookmark(),
+                FileExplorerCommand::CycleFilter => self.cycle_filter(),
+                FileExplorerCommand::ToggleTreeMode => self.toggle_tree_mode(),
+                FileExplorerCommand::ToggleMark => self.toggle_mark(),
+                FileExplorerCommand::MarkAll => self.mark_all(),
+                FileExplorerCommand::InvertMarks => self.invert_marks(),
+                FileExplorerCommand::CycleSortMode => self.cycle_sort_mode(),
+                FileExplorerCommand::ToggleSortDirection => self.toggle_sort_direction(),
+                FileExplorerCommand::StartSearch(initial) => self.start_search(initial),
+                FileExplorerCommand::SearchInput(inner) => self.handle_search_input(inner),
+                FileExplorerCommand::EditPath => self.start_path_edit(),
+                FileExplorerCommand::PathInput(inner) => self.handle_path_input(inner),
+                FileExplorerCommand::EnterFileName => self.start_file_name(),
+                FileExplorerCommand::CreateDirectory => self.start_create_directory(),
+                FileExplorerCommand::Rename => self.start_rename(),
+                FileExplorerCommand::Delete => self.start_delete(),
+                FileExplorerCommand::CopyPath => self.copy_path(),
+                FileExplorerCommand::PopupInput(inner) => self.handle_popup_input(inner),
+                FileExplorerCommand::ConfirmPopup => self.confirm_popup(),
+                FileExplorerCommand::CancelPopup => self.cancel_popup(),
+                FileExplorerCommand::Exit => Ok(FileExplorerEvent::Cancelled),
             }
         } else {
+            self.poll();
             Ok(FileExplorerEvent::Selecting)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::explorer::provider::MemoryFileSystem;
+    use crate::widgets::explorer::sort::SortKey;
+    use crate::widgets::util::cursor::{DefaultTypingBehaviour, SimpleCursorToCharIndexMapper};
+
+    type Behaviour = DefaultTypingBehaviour<SimpleCursorToCharIndexMapper>;
+
+    fn explorer() -> FileExplorerState<Behaviour> {
+        let provider = MemoryFileSystem::new()
+            .with_file("/home/file10.txt", "ten")
+            .with_file("/home/file9.txt", "nine")
+            .with_file("/home/notes.md", "notes")
+            .with_file("/home/docs/report.txt", "report")
+            .with_directory("/home/docs/archive");
+        let mut state = FileExplorerState::with_provider(
+            provider, PathBuf::from("/home"), FileDialogType::AskFilename
+        ).unwrap();
+        state.wait_until_loaded();
+        state
+    }
+
+    fn names(state: &FileExplorerState<Behaviour>) -> Vec<String> {
+        state.files.iter()
+            .map(|row| row.info.display_name().into_owned())
+            .collect()
+    }
+
+    fn run(state: &mut FileExplorerState<Behaviour>, command: FileExplorerCommand) -> FileExplorerEvent {
+        let event = state.handle_native_event(Some(command)).unwrap();
+        state.wait_until_loaded();
+        event
+    }
+
+    #[test]
+    fn lists_directories_first_sorted_by_name() {
+        let state = explorer();
+        assert_eq!(names(&state), ["docs", "file10.txt", "file9.txt", "notes.md"]);
+    }
+
+    #[test]
+    fn sorts_numbers_naturally() {
+        let mut state = explorer();
+        state.set_sort_order(SortOrder::new(SortKey::NaturalName));
+        assert_eq!(names(&state), ["docs", "file9.txt", "file10.txt", "notes.md"]);
+    }
+
+    #[test]
+    fn enters_and_leaves_directories() {
+        let mut state = explorer();
+        run(&mut state, FileExplorerCommand::DirectoryDown);
+        assert_eq!(state.directory, Path::new("/home/docs"));
+        assert_eq!(names(&state), ["archive", "report.txt"]);
+        run(&mut state, FileExplorerCommand::DirectoryUp);
+        assert_eq!(state.directory, Path::new("/home"));
+        assert_eq!(state.selected_path(), Some(PathBuf::from("/home/docs")));
+    }
+
+    #[test]
+    fn selects_files_on_confirm() {
+        let mut state = explorer();
+        run(&mut state, FileExplorerCommand::ArrowDown);
+        assert_eq!(
+            run(&mut state, FileExplorerCommand::Confirm),
+            FileExplorerEvent::Selected(PathBuf::from("/home/file10.txt"))
+        );
+    }
+
+    #[test]
+    fn filters_files_but_not_directories() {
+        let mut state = explorer()
+            .with_filters(vec![FileFilter::new("Text", ["*.txt"])]);
+        assert_eq!(names(&state), ["docs", "file10.txt", "file9.txt"]);
+        run(&mut state, FileExplorerCommand::CycleFilter);
+        assert_eq!(names(&state), ["docs", "file10.txt", "file9.txt", "notes.md"]);
+    }
+
+    #[test]
+    fn expands_and_collapses_directories_in_tree_mode() {
+        let mut state = explorer().with_tree_mode(true);
+        run(&mut state, FileExplorerCommand::DirectoryDown);
+        assert_eq!(state.directory, Path::new("/home"));
+        assert_eq!(
+            names(&state),
+            ["docs", "archive", "report.txt", "file10.txt", "file9.txt", "notes.md"]
+        );
+        assert_eq!(state.files.iter().map(|row| row.depth).collect::<Vec<_>>(), [0, 1, 1, 0, 0, 0]);
+        run(&mut state, FileExplorerCommand::DirectoryUp);
+        assert_eq!(names(&state), ["docs", "file10.txt", "file9.txt", "notes.md"]);
+    }
+}
@@ -0,0 +1,253 @@
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::border;
+
+use crate::widgets::explorer::sidebar::SidebarSection;
+use crate::widgets::explorer::state::FileType;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Icon Sets
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The icons shown in front of file names and sidebar entries.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum IconSet {
+    #[default]
+    Emoji,
+    /// Requires a terminal font patched with Nerd Font glyphs.
+    NerdFont,
+    /// Type indicators in the style of `ls -F`, for terminals
+    /// which render emoji at the wrong width.
+    Ascii,
+    None
+}
+
+impl IconSet {
+    // Width of the icon columns in the file list
+    pub(super) fn width(&self) -> u16 {
+        match self {
+            IconSet::Emoji | IconSet::NerdFont => 2,
+            IconSet::Ascii => 1,
+            IconSet::None => 0
+        }
+    }
+
+    pub(super) fn file_type(&self, file_type: FileType) -> &'static str {
+        match (self, file_type) {
+            (IconSet::Emoji, _) if file_type.is_dir() => "\u{1F4C1}",
+            (IconSet::Emoji, _) => "\u{1F4C4}",
+            (IconSet::NerdFont, _) if file_type.is_dir() => "\u{F07B}",
+            (IconSet::NerdFont, FileType::BrokenSymlink) => "\u{F127}",
+            (IconSet::NerdFont, FileType::Socket) => "\u{F1E6}",
+            (IconSet::NerdFont, FileType::Fifo) => "\u{F07E}",
+            (IconSet::NerdFont, FileType::BlockDevice | FileType::CharDevice) => "\u{F0A0}",
+            (IconSet::NerdFont, _) => "\u{F15B}",
+            (IconSet::Ascii, _) if file_type.is_dir() => "/",
+            (IconSet::Ascii, FileType::BrokenSymlink) => "!",
+            (IconSet::Ascii, FileType::Socket) => "=",
+            (IconSet::Ascii, FileType::Fifo) => "|",
+            (IconSet::Ascii, FileType::BlockDevice | FileType::CharDevice) => "#",
+            (IconSet::Ascii, _) => " ",
+            (IconSet::None, _) => ""
+        }
+    }
+
+    pub(super) fn symlink(&self) -> &'static str {
+        match self {
+            IconSet::Emoji => "\u{1F517}",
+            IconSet::NerdFont => "\u{F0C1}",
+            IconSet::Ascii => "@",
+            IconSet::None => ""
+        }
+    }
+
+    // Includes the separating space, if any
+    pub(super) fn sidebar_section(&self, section: SidebarSection) -> &'static str {
+        match (self, section) {
+            (IconSet::Emoji, SidebarSection::Places) => "\u{2302} ",
+            (IconSet::Emoji, SidebarSection::Volumes) => "\u{26C1} ",
+            (IconSet::Emoji, SidebarSection::Bookmarks) => "\u{2605} ",
+            (IconSet::Emoji, SidebarSection::Recent) => "\u{21BA} ",
+            (IconSet::NerdFont, SidebarSection::Places) => "\u{F015} ",
+            (IconSet::NerdFont, SidebarSection::Volumes) => "\u{F0A0} ",
+            (IconSet::NerdFont, SidebarSection::Bookmarks) => "\u{F005} ",
+            (IconSet::NerdFont, SidebarSection::Recent) => "\u{F1DA} ",
+            (IconSet::Ascii, SidebarSection::Places) => "~ ",
+            (IconSet::Ascii, SidebarSection::Volumes) => "# ",
+            (IconSet::Ascii, SidebarSection::Bookmarks) => "* ",
+            (IconSet::Ascii, SidebarSection::Recent) => "< ",
+            (IconSet::None, _) => ""
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Theme
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+// Style of the names of each type of entry
+#[derive(Debug, Copy, Clone)]
+struct FileStyles {
+    file: Style,
+    directory: Style,
+    file_symlink: Style,
+    directory_symlink: Style,
+    broken_symlink: Style,
+    socket: Style,
+    fifo: Style,
+    block_device: Style,
+    char_device: Style
+}
+
+impl FileStyles {
+    fn get_mut(&mut self, file_type: FileType) -> &mut Style {
+        match file_type {
+            FileType::File => &mut self.file,
+            FileType::Directory => &mut self.directory,
+            FileType::FileSymlink => &mut self.file_symlink,
+            FileType::DirectorySymlink => &mut self.directory_symlink,
+            FileType::BrokenSymlink => &mut self.broken_symlink,
+            FileType::Socket => &mut self.socket,
+            FileType::Fifo => &mut self.fifo,
+            FileType::BlockDevice => &mut self.block_device,
+            FileType::CharDevice => &mut self.char_device
+        }
+    }
+
+    fn get(&self, file_type: FileType) -> Style {
+        match file_type {
+            FileType::File => self.file,
+            FileType::Directory => self.directory,
+            FileType::FileSymlink => self.file_symlink,
+            FileType::DirectorySymlink => self.directory_symlink,
+            FileType::BrokenSymlink => self.broken_symlink,
+            FileType::Socket => self.socket,
+            FileType::Fifo => self.fifo,
+            FileType::BlockDevice => self.block_device,
+            FileType::CharDevice => self.char_device
+        }
+    }
+}
+
+/// Colours, icons and labels used to draw a `FileExplorer`.
+#[derive(Debug, Clone)]
+pub struct FileExplorerTheme {
+    file_styles: FileStyles,
+    highlight_style: Style,
+    key_style: Style,
+    icons: IconSet,
+    border_set: border::Set,
+    title: String,
+    // Replaces the generated key hints
+    help_text: Option<String>,
+    show_help: bool
+}
+
+impl Default for FileExplorerTheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileExplorerTheme {
+    pub fn new() -> Self {
+        Self {
+            file_styles: FileStyles {
+                file: Style::new().light_red(),
+                directory: Style::new().cyan(),
+                file_symlink: Style::new().light_magenta(),
+                directory_symlink: Style::new().light_blue(),
+                broken_symlink: Style::new().red().crossed_out(),
+                socket: Style::new().yellow(),
+                fifo: Style::new().yellow(),
+                block_device: Style::new().light_yellow(),
+                char_device: Style::new().light_yellow()
+            },
+            highlight_style: Style::new().bg(Color::LightYellow),
+            key_style: Style::new().blue().bold(),
+            icons: IconSet::default(),
+            border_set: border::THICK,
+            title: "Select a File".to_string(),
+            help_text: None,
+            show_help: true
+        }
+    }
+
+    /// Style of the names of entries with the given type.
+    pub fn with_file_style(mut self, file_type: FileType, style: Style) -> Self {
+        *self.file_styles.get_mut(file_type) = style;
+        self
+    }
+
+    /// Style of the selected row in the file list and the sidebar.
+    pub fn with_highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Style of the keys in the key hints.
+    pub fn with_key_style(mut self, style: Style) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    pub fn with_icons(mut self, icons: IconSet) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Border of the explorer and its popups.
+    pub fn with_border_set(mut self, border_set: border::Set) -> Self {
+        self.border_set = border_set;
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Show the given text at the bottom of the explorer instead of the key hints.
+    pub fn with_help_text(mut self, text: impl Into<String>) -> Self {
+        self.help_text = Some(text.into());
+        self
+    }
+
+    pub fn with_help(mut self, show_help: bool) -> Self {
+        self.show_help = show_help;
+        self
+    }
+
+    pub(super) fn file_style(&self, file_type: FileType) -> Style {
+        self.file_styles.get(file_type)
+    }
+
+    pub(super) fn highlight_style(&self) -> Style {
+        self.highlight_style
+    }
+
+    pub(super) fn key_style(&self) -> Style {
+        self.key_style
+    }
+
+    pub(super) fn icons(&self) -> IconSet {
+        self.icons
+    }
+
+    pub(super) fn border_set(&self) -> border::Set {
+        self.border_set
+    }
+
+    pub(super) fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub(super) fn help_text(&self) -> Option<&str> {
+        self.help_text.as_deref()
+    }
+
+    pub(super) fn show_help(&self) -> bool {
+        self.show_help
+    }
+}
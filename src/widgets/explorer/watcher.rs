@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::widgets::explorer::loader::LoadNotifier;
+
+pub(super) enum WatchChange {
+    // Entries of the watched directory which were created, changed or removed
+    Entries(Vec<PathBuf>),
+    // Events were lost, so the whole directory must be read again
+    Rescan
+}
+
+impl WatchChange {
+    pub(super) fn merge(self, other: WatchChange) -> WatchChange {
+        match (self, other) {
+            (WatchChange::Entries(mut paths), WatchChange::Entries(more)) => {
+                for path in more {
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+                WatchChange::Entries(paths)
+            }
+            _ => WatchChange::Rescan
+        }
+    }
+}
+
+/// Watches a single directory (non-recursively) for changes.
+pub(super) struct DirectoryWatcher {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<notify::Event>>,
+    path: PathBuf,
+    // Called from the watcher thread after each event
+    notifier: Arc<Mutex<Option<LoadNotifier>>>
+}
+
+impl DirectoryWatcher {
+    pub(super) fn new(path: &Path, on_change: Option<LoadNotifier>) -> anyhow::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let notifier = Arc::new(Mutex::new(on_change));
+        let callback_notifier = notifier.clone();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+            if let Some(on_change) = callback_notifier.lock().ok().and_then(|notifier| notifier.clone()) {
+                on_change();
+            }
+        })?;
+        watcher.watch(path, RecursiveMode::NonRecursive)?;
+        Ok(Self { watcher, receiver, path: path.to_path_buf(), notifier })
+    }
+
+    pub(super) fn set_notifier(&self, on_change: Option<LoadNotifier>) {
+        if let Ok(mut notifier) = self.notifier.lock() {
+            *notifier = on_change;
+        }
+    }
+
+    pub(super) fn set_path(&mut self, path: &Path) -> anyhow::Result<()> {
+        if path == self.path {
+            return Ok(());
+        }
+        let _ = self.watcher.unwatch(&self.path);
+        // Drop events for the previous directory
+        while self.receiver.try_recv().is_ok() {}
+        self.path = path.to_path_buf();
+        self.watcher.watch(path, RecursiveMode::NonRecursive)?;
+        Ok(())
+    }
+
+    /// Collect the changes since the last call.
+    pub(super) fn changes(&self) -> Option<WatchChange> {
+        let mut changed = Vec::new();
+        for event in self.receiver.try_iter() {
+            match event {
+                Ok(event) if !event.need_rescan() => {
+                    changed.extend(
+                        event.paths
+                            .into_iter()
+                            .filter(|path| path.parent() == Some(self.path.as_path()))
+                    );
+                }
+                _ => {
+                    while self.receiver.try_recv().is_ok() {}
+                    return Some(WatchChange::Rescan);
+                }
+            }
+        }
+        if changed.is_empty() {
+            return None;
+        }
+        changed.sort();
+        changed.dedup();
+        Some(WatchChange::Entries(changed))
+    }
+}
@@ -1,22 +1,102 @@
 use std::marker::PhantomData;
+use std::time::SystemTime;
 
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Style, Stylize};
-use ratatui::symbols::border;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Paragraph, Row, StatefulWidget, StatefulWidgetRef, Table, Widget};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Row, StatefulWidget, StatefulWidgetRef, Table, Widget, Wrap};
 use ratatui::widgets::block::{Position, Title};
 
-use crate::widgets::core::StatefulRemyWidget;
+use crate::widgets::core::{CursorPosition, CursorProvider, StatefulRemyWidget};
 use crate::widgets::text_input::TextInput;
 use crate::widgets::util::cursor::TypingBehaviour;
-use super::state::{FileDialogType, FileExplorerState};
-use super::state::FileType;
+use super::state::{FileDialogType, FileExplorerPopup, FileExplorerState};
+use super::state::{FileInformation, FileRow};
+use super::format;
+use super::search::fuzzy_match;
+use super::sidebar::SidebarSection;
+use super::theme::FileExplorerTheme;
+use super::preview::{Preview, PreviewContent};
 
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FileExplorerColumn {
+    Size,
+    Modified,
+    Permissions,
+    Owner,
+    SymlinkTarget
+}
+
+impl FileExplorerColumn {
+    fn header(&self) -> &'static str {
+        match self {
+            FileExplorerColumn::Size => "Size",
+            FileExplorerColumn::Modified => "Modified",
+            FileExplorerColumn::Permissions => "Mode",
+            FileExplorerColumn::Owner => "Owner",
+            FileExplorerColumn::SymlinkTarget => "Target"
+        }
+    }
+
+    // Given the rows which are currently visible
+    fn constraint(&self, rows: &[FileRow]) -> Constraint {
+        match self {
+            FileExplorerColumn::Size => Constraint::Length(6),
+            FileExplorerColumn::Modified => Constraint::Length(9),
+            FileExplorerColumn::Permissions => Constraint::Length(10),
+            FileExplorerColumn::Owner => Constraint::Length(
+                rows.iter()
+                    .filter_map(|row| Self::owner(&row.info))
+                    .map(|owner| owner.len() as u16)
+                    .fold(self.header().len() as u16, u16::max)
+            ),
+            FileExplorerColumn::SymlinkTarget => Constraint::Percentage(30)
+        }
+    }
+
+    fn cell<'a>(&self, info: &FileInformation, now: SystemTime) -> Line<'a> {
+        match self {
+            FileExplorerColumn::Size if info.file_type.is_dir() => Line::raw("-"),
+            FileExplorerColumn::Size => Line::raw(format::human_size(info.size)).alignment(Alignment::Right),
+            FileExplorerColumn::Modified => Line::raw(
+                info.modified
+                    .map(|time| format::relative_time(time, now))
+                    .unwrap_or_default()
+            ),
+            FileExplorerColumn::Permissions => Line::raw(
+                info.permissions
+                    .map(|mode| format::permissions(mode, info.file_type))
+                    .unwrap_or_default()
+            ),
+            FileExplorerColumn::Owner => Line::raw(Self::owner(info).unwrap_or_default()),
+            FileExplorerColumn::SymlinkTarget => Line::raw(
+                info.symlink_target
+                    .as_ref()
+                    .map(|target| format!("\u{2192} {}", target.display()))
+                    .unwrap_or_default()
+            )
+        }
+    }
+
+    fn owner(info: &FileInformation) -> Option<String> {
+        info.owner.map(|(uid, gid)| format!("{}:{}", uid, gid))
+    }
+}
+
+
+/// Draws a `FileExplorerState`. This widget is `Clone` but not `Copy`, since its
+/// theme owns the title and help text; keep it around instead of rebuilding it
+/// for every frame.
+#[derive(Debug, Clone)]
 pub struct FileExplorer<T: TypingBehaviour> {
+    columns: Vec<FileExplorerColumn>,
+    sidebar_width: u16,
+    show_preview: bool,
+    preview_width: u16,
+    preview_limit: usize,
+    theme: FileExplorerTheme,
     _behaviour: PhantomData<T>
 }
 
@@ -25,140 +105,437 @@ pub struct FileExplorer<T: TypingBehaviour> {
 impl<T: TypingBehaviour> FileExplorer<T> {
     pub fn new() -> Self {
         Self {
+            columns: Vec::new(),
+            sidebar_width: 24,
+            show_preview: false,
+            preview_width: 40,
+            preview_limit: 16 * 1024,
+            theme: FileExplorerTheme::default(),
             _behaviour: PhantomData
         }
     }
-    
-    pub fn cursor(&self, area: Rect, state: &mut FileExplorerState<T>) -> Option<(u16, u16)> {
-        let render = FileExplorerRenderer::new(area, state.dialog_type, state);
-        render.cursor()
+
+    /// Metadata columns shown after the file name, in the given order.
+    /// Repeated columns are only shown once.
+    pub fn with_columns(mut self, columns: impl IntoIterator<Item = FileExplorerColumn>) -> Self {
+        self.columns.clear();
+        for column in columns {
+            if !self.columns.contains(&column) {
+                self.columns.push(column);
+            }
+        }
+        self
+    }
+
+    /// Width of the sidebar, which is shown if the state has one.
+    pub fn with_sidebar_width(mut self, width: u16) -> Self {
+        self.sidebar_width = width;
+        self
+    }
+
+    pub fn with_preview(mut self, show_preview: bool) -> Self {
+        self.show_preview = show_preview;
+        self
+    }
+
+    /// Width of the preview panel as a percentage of the file list area.
+    pub fn with_preview_width(mut self, percentage: u16) -> Self {
+        self.preview_width = percentage.min(100);
+        self
+    }
+
+    /// Maximum number of bytes read from a file to preview it.
+    pub fn with_preview_limit(mut self, bytes: usize) -> Self {
+        self.preview_limit = bytes;
+        self
     }
 
-    
+    pub fn with_theme(mut self, theme: FileExplorerTheme) -> Self {
+        self.theme = theme;
+        self
+    }
 }
 
+// A text input together with its surrounding decoration
+type DecoratedInput<D, T> = ((Rect, D), (Rect, TextInput<T>));
+
 pub(super) struct FileExplorerRenderer<'a, T: TypingBehaviour> {
     outer_block: (Rect, Block<'a>),
     table: (Rect, Table<'a>),
+    sidebar: Option<(Rect, List<'a>)>,
+    preview: Option<(Rect, Paragraph<'a>)>,
     status: (Rect, Paragraph<'a>),
-    input: Option<((Rect, Block<'a>), (Rect, TextInput<T>))>,
-    cursor: Option<(u16, u16)>,
+    path: Option<DecoratedInput<Span<'a>, T>>,
+    input: Option<DecoratedInput<Block<'a>, T>>,
+    confirm: Option<(Rect, Paragraph<'a>)>,
+    search: Option<DecoratedInput<Span<'a>, T>>,
+    cursor: Option<CursorPosition>,
 }
 
 impl<'a, T: TypingBehaviour> FileExplorerRenderer<'a, T> {
 
     pub(super) fn new(area: Rect,
+                      explorer: &FileExplorer<T>,
                       file_dialog_type: FileDialogType,
                       state: &mut FileExplorerState<T>) -> Self
     {
-        let block = Self::draw_main_border(file_dialog_type);
+        state.poll();
+        let theme = &explorer.theme;
+        let block = Self::draw_main_border(file_dialog_type, state, theme);
         let inner = block.inner(area);
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Max(1),
-                Constraint::Min(1)
+                Constraint::Min(1),
+                Constraint::Length(if state.search_input_state.is_some() { 1 } else { 0 })
             ])
             .split(inner);
         let status = Self::draw_status(state);
-        let table = Self::draw_file_list( file_dialog_type, state);
-        let (cursor, input) = if let Some(ref mut inner_state) = state.filename_input_state {
-            let input = Self::draw_name_popup(area);
-            let cursor = inner_state.get_cursor_location(inner.width as usize) as u16;
-            (Some((cursor, area.y)), Some(input))
+        let (sidebar_area, table_area) = if state.sidebar.is_some() {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(explorer.sidebar_width),
+                    Constraint::Min(1)
+                ])
+                .split(chunks[1]);
+            (columns[0], columns[1])
+        } else {
+            (Rect::default(), chunks[1])
+        };
+        let (table_area, preview) = if explorer.show_preview {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Min(1),
+                    Constraint::Percentage(explorer.preview_width)
+                ])
+                .split(table_area);
+            let show_hidden = state.show_hidden;
+            let preview = Self::draw_preview(state.preview(explorer.preview_limit), show_hidden, theme);
+            (columns[0], Some((columns[1], preview)))
+        } else {
+            (table_area, None)
+        };
+        let sidebar = state.sidebar.as_ref().map(|_| (sidebar_area, Self::draw_sidebar(state, theme)));
+        let header_height = if explorer.columns.is_empty() { 0 } else { 1 };
+        state.page_size = table_area.height.saturating_sub(header_height) as usize;
+        let table = Self::draw_file_list(&explorer.columns, state, theme);
+        let mut cursor = None;
+        let search = if let Some(ref mut search_state) = state.search_input_state {
+            let search = Self::draw_labelled_input(chunks[2], "Search: ");
+            if state.search_focused {
+                let (input_area, ref input_field) = search.1;
+                cursor = input_field.cursor(input_area, search_state);
+            }
+            Some(search)
         } else {
-            (None, None)
+            None
         };
+        let path = if let Some(ref mut path_state) = state.path_input_state {
+            let path = Self::draw_labelled_input(chunks[0], "Path: ");
+            let (input_area, ref input_field) = path.1;
+            cursor = input_field.cursor(input_area, path_state);
+            Some(path)
+        } else {
+            None
+        };
+        let mut input = None;
+        let mut confirm = None;
+        match state.popup {
+            Some(FileExplorerPopup::ConfirmDelete(ref path, _) | FileExplorerPopup::ConfirmOverwrite(ref path, _)) => {
+                let name = path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+                let (title, question, action, keys) = match state.popup {
+                    Some(FileExplorerPopup::ConfirmDelete(_, true)) => {
+                        ("Confirm Delete", format!("Delete '{}' and all of its contents?", name), " Delete ", "y")
+                    }
+                    Some(FileExplorerPopup::ConfirmDelete(_, false)) => {
+                        ("Confirm Delete", format!("Delete '{}'?", name), " Delete ", "y")
+                    }
+                    _ => ("Confirm Overwrite", format!("'{}' already exists. Replace it?", name), " Replace ", "y/Enter")
+                };
+                confirm = Some(Self::draw_confirm_popup(area, title, question, action, keys, theme));
+            }
+            Some(ref mut popup) => {
+                let title = match popup {
+                    FileExplorerPopup::NewDirectory(_) => "New Directory",
+                    FileExplorerPopup::Rename(..) => "Rename",
+                    _ => "Enter a Filename"
+                };
+                let popup_input = Self::draw_name_popup(area, title, theme);
+                let (input_area, ref input_field) = popup_input.1;
+                if let Some(inner_state) = popup.input_mut() {
+                    cursor = input_field.cursor(input_area, inner_state);
+                }
+                input = Some(popup_input);
+            }
+            None => ()
+        }
         Self {
             outer_block: (area, block),
             status: (chunks[0], status),
-            table: (chunks[1], table),
+            path,
+            table: (table_area, table),
+            sidebar,
+            preview,
             input,
+            confirm,
+            search,
             cursor
         }
     }
 
-    pub(super) fn cursor(&self) -> Option<(u16, u16)> {
+    pub(super) fn cursor(&self) -> Option<CursorPosition> {
         self.cursor
     }
 
-    fn draw_main_border(file_dialog_type: FileDialogType) -> Block<'a> {
-        let instructions = Title::from(
-            Line::from(
-                vec![
-                    " File Up/Down ".into(),
-                    "<\u{2191}/\u{2193}>".blue().bold(),
-                    " Directory Up/Down ".into(),
-                    "<\u{2190}/\u{2192}>".blue().bold(),
-                    if file_dialog_type == FileDialogType::AskSaveAsFilename {
-                        " Enter Filename ".into()
-                    } else {
-                        "".into()
-                    },
-                    if file_dialog_type == FileDialogType::AskSaveAsFilename {
-                        "<n>".blue().bold()
-                    } else {
-                        "".into()
-                    },
-                    " Confirm ".into(),
-                    "<Enter>".blue().bold(),
-                    " Cancel ".into(),
-                    "<Esc>".blue().bold(),
-                ]
-            )
-        );
-        Block::bordered()
-            .title(Title::from("Select a File".bold()).alignment(Alignment::Center))
-            .title(instructions.alignment(Alignment::Center).position(Position::Bottom))
-            .border_set(border::THICK)
+    fn draw_main_border(file_dialog_type: FileDialogType,
+                        state: &FileExplorerState<T>,
+                        theme: &FileExplorerTheme) -> Block<'a> {
+        let block = Block::bordered()
+            .title(Title::from(theme.title().to_string().bold()).alignment(Alignment::Center))
+            .border_set(theme.border_set());
+        if !theme.show_help() {
+            return block;
+        }
+        let instructions = match theme.help_text() {
+            Some(text) => Line::raw(text.to_string()),
+            None => Self::draw_key_hints(file_dialog_type, state, theme.key_style())
+        };
+        block.title(Title::from(instructions).alignment(Alignment::Center).position(Position::Bottom))
+    }
+
+    fn draw_key_hints(file_dialog_type: FileDialogType,
+                      state: &FileExplorerState<T>,
+                      key_style: Style) -> Line<'a> {
+        let multiple = file_dialog_type.allow_multiple();
+        let operations = state.operations;
+        let hints = [
+            (true, " File Up/Down ", "<\u{2191}/\u{2193}>"),
+            (true, " Directory Up/Down ", "<\u{2190}/\u{2192}>"),
+            (file_dialog_type == FileDialogType::AskSaveAsFilename, " Enter Filename ", "<C-n>"),
+            (multiple, " Mark/All/Invert ", "<Space/C-a/C-r>"),
+            (operations.create_directory(), " New Directory ", "<F7>"),
+            (operations.rename(), " Rename ", "<F2>"),
+            (operations.delete(), " Delete ", "<Del>"),
+            (operations.copy_path(), " Copy Path ", "<C-y>"),
+            (state.sidebar.is_some(), " Sidebar/Bookmark ", "<Tab/C-b>"),
+            (true, " Path ", "<C-l>"),
+            (true, " Search ", "</>"),
+            (true, " Hidden ", "<C-h>"),
+            (true, " Sort ", "<F3/S-F3>"),
+            (true, " Tree ", "<C-t>"),
+            (!state.filters.is_empty(), " Filter ", "<C-f>"),
+            (true, " Confirm ", "<Enter>"),
+            (true, " Cancel ", "<Esc>")
+        ];
+        Line::from(
+            hints.into_iter()
+                .filter(|(shown, _, _)| *shown)
+                .flat_map(|(_, label, key)| [Span::raw(label), Span::styled(key, key_style)])
+                .collect::<Vec<_>>()
+        )
     }
 
     fn draw_status(state: &FileExplorerState<T>) -> Paragraph<'a> {
-        let dir_name = state.directory
-            .clone()
-            .into_os_string()
-            .into_string()
-            .expect("Failed to convert path name");
-        let text = format!("Current directory: {}", dir_name);
+        let text = format!("Current directory: {}", state.directory.display());
+        let filter = match state.active_filter() {
+            Some(filter) => format!(" [{}]", filter),
+            None if !state.filters.is_empty() => " [All files]".to_string(),
+            None => String::new()
+        };
+        let hidden = if state.show_hidden { " [hidden shown]" } else { "" };
+        let sort = format!(" [Sort: {}]", state.sort_order);
+        let loading = match state.loading_count() {
+            Some(count) => format!(" [Loading {} entries\u{2026}]", count),
+            None => String::new()
+        };
         let error = state.io_error.as_ref().cloned().unwrap_or_default();
         let line = Line::from(vec![
             Span::styled(text, Style::new().fg(Color::Green).bold()),
+            Span::styled(filter, Style::new().fg(Color::Yellow)),
+            Span::styled(hidden, Style::new().fg(Color::Yellow)),
+            Span::styled(sort, Style::new().fg(Color::Yellow)),
+            Span::styled(loading, Style::new().fg(Color::Magenta).bold()),
             Span::styled(error, Style::new().fg(Color::White).bg(Color::Red).bold())
         ]);
         Paragraph::new(line).alignment(Alignment::Left)
     }
 
-    fn draw_file_list(file_dialog_type: FileDialogType,
-                      state: &FileExplorerState<T>) -> Table<'a>
+    fn draw_file_list(columns: &[FileExplorerColumn],
+                      state: &FileExplorerState<T>,
+                      theme: &FileExplorerTheme) -> Table<'a>
     {
+        let icons = theme.icons();
+        let now = SystemTime::now();
+        let multiple = state.dialog_type.allow_multiple();
         let table = Table::new(
             state.files.iter()
-                .filter(
-                    |info| (!info.file_type.is_file()) || file_dialog_type.show_filenames()
-                )
-                .map(|info| {
-                    let style = match info.file_type {
-                        FileType::File => Style::new().light_red(),
-                        FileType::Directory => Style::new().cyan(),
-                        FileType::FileSymlink => Style::new().light_magenta(),
-                        FileType::DirectorySymlink => Style::new().light_blue(),
-                    };
-                    Row::new(vec![
-                        Span::raw(if info.file_type.is_symlink() { "\u{1F517}" } else { "" }),
-                        Span::raw(if info.file_type.is_dir() { "\u{1F4C1}" } else { "\u{1F4C4}" }),
-                        Span::styled(info.name.clone(), style)
-                    ])
+                .map(|row| {
+                    let info = &row.info;
+                    let style = theme.file_style(info.file_type);
+                    let matches = state.search_query()
+                        .and_then(|query| fuzzy_match(query, &info.display_name()))
+                        .unwrap_or_default();
+                    let mut cells = Vec::new();
+                    if multiple {
+                        cells.push(
+                            if state.is_marked(row) {
+                                Line::from("\u{25CF}".green().bold())
+                            } else {
+                                Line::raw(" ")
+                            }
+                        );
+                    }
+                    cells.extend([
+                        Line::raw(if info.file_type.is_symlink() { icons.symlink() } else { "" }),
+                        Line::raw(icons.file_type(info.file_type)),
+                        Self::draw_name(row, state.tree_mode, &matches, style)
+                    ]);
+                    cells.extend(columns.iter().map(|column| column.cell(info, now)));
+                    Row::new(cells)
                 }),
-            [
-                Constraint::Length(2),
-                Constraint::Length(2),
-                Constraint::Min(1)
-            ]
+            multiple.then_some(Constraint::Length(1))
+                .into_iter()
+                .chain([
+                    Constraint::Length(icons.width()),
+                    Constraint::Length(icons.width()),
+                    Constraint::Min(1)
+                ])
+                .chain(columns.iter().map(|column| column.constraint(state.visible_rows())))
         );
-        table.highlight_style(Style::new().bg(Color::LightYellow))
+        let table = if columns.is_empty() {
+            table
+        } else {
+            let header = multiple.then_some("")
+                .into_iter()
+                .chain(["", "", "Name"])
+                .chain(columns.iter().map(|column| column.header()));
+            table.header(Row::new(header).bold())
+        };
+        table.highlight_style(theme.highlight_style())
+    }
+
+    fn draw_sidebar(state: &FileExplorerState<T>, theme: &FileExplorerTheme) -> List<'a> {
+        let entries = state.sidebar
+            .as_ref()
+            .map(|sidebar| sidebar.entries())
+            .unwrap_or_default();
+        let items = entries.into_iter()
+            .map(|entry| {
+                let icon = theme.icons().sidebar_section(entry.section);
+                let style = match entry.section {
+                    SidebarSection::Places => Style::new().cyan(),
+                    SidebarSection::Volumes => Style::new().light_blue(),
+                    SidebarSection::Bookmarks => Style::new().yellow(),
+                    SidebarSection::Recent => Style::new().gray()
+                };
+                ListItem::new(Line::styled(format!("{}{}", icon, entry.label), style))
+            });
+        let highlight = if state.sidebar_focused {
+            theme.highlight_style()
+        } else {
+            Style::new().bold()
+        };
+        List::new(items)
+            .block(Block::new().borders(Borders::RIGHT))
+            .highlight_style(highlight)
+    }
+
+    fn draw_preview(preview: Option<&Preview>, show_hidden: bool, theme: &FileExplorerTheme) -> Paragraph<'a> {
+        let block = Block::new()
+            .borders(Borders::LEFT)
+            .title(Title::from(" Preview ".bold()));
+        let Some(preview) = preview else {
+            return Paragraph::new("").block(block);
+        };
+        let mut lines = Vec::new();
+        if let Some(ref target) = preview.symlink_target {
+            lines.push(Line::from(format!("\u{2192} {}", target.display()).light_magenta()));
+        }
+        match &preview.content {
+            PreviewContent::Text(text) => {
+                lines.extend(text.iter().map(|line| Line::raw(line.clone())));
+            }
+            PreviewContent::Binary(dump) => {
+                lines.extend(dump.iter().map(|line| Line::from(line.clone().gray())));
+            }
+            PreviewContent::Directory(entries) => {
+                lines.extend(
+                    entries.iter()
+                        .filter(|info| show_hidden || !info.is_hidden())
+                        .map(|info| {
+                            let icon = theme.icons().file_type(info.file_type);
+                            Line::raw(format!("{} {}", icon, info.display_name()))
+                        })
+                );
+            }
+            PreviewContent::Unavailable(file_type) => {
+                lines.push(Line::from(format!("No preview for {}", file_type.description()).dark_gray()));
+            }
+            PreviewContent::Error(error) => {
+                lines.push(Line::from(error.clone().red()));
+            }
+        }
+        if preview.truncated {
+            lines.push(Line::from("\u{2026}".dark_gray()));
+        }
+        Paragraph::new(lines).block(block)
+    }
+
+    fn draw_name(row: &FileRow, tree_mode: bool, matches: &[usize], style: Style) -> Line<'a> {
+        let mut prefix = String::new();
+        if row.depth > 0 {
+            // The top level has no connectors, so neither does its guide
+            for &continues in &row.guides[1..] {
+                prefix.push_str(if continues { "\u{2502}  " } else { "   " });
+            }
+            prefix.push_str(if row.last { "\u{2514}\u{2500} " } else { "\u{251C}\u{2500} " });
+        }
+        if tree_mode && row.info.file_type.is_dir() {
+            prefix.push_str(if row.expanded { "\u{25BE} " } else { "\u{25B8} " });
+        }
+        let name = Self::highlight_matches(&row.info.display_name(), matches, style);
+        if prefix.is_empty() {
+            return name;
+        }
+        let mut spans = vec![Span::styled(prefix, Style::new().dark_gray())];
+        spans.extend(name.spans);
+        Line::from(spans)
+    }
+
+    fn highlight_matches(name: &str, matches: &[usize], style: Style) -> Line<'a> {
+        if matches.is_empty() {
+            return Line::styled(name.to_string(), style);
+        }
+        let highlight = style.bold().underlined();
+        Line::from(
+            name.chars()
+                .enumerate()
+                .map(|(index, c)| {
+                    let style = if matches.contains(&index) { highlight } else { style };
+                    Span::styled(c.to_string(), style)
+                })
+                .collect::<Vec<_>>()
+        )
+    }
+
+    fn draw_labelled_input(area: Rect, label: &'a str) -> DecoratedInput<Span<'a>, T> {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(label.chars().count() as u16),
+                Constraint::Min(1)
+            ])
+            .split(area);
+        let label = label.blue().bold();
+        ((chunks[0], label), (chunks[1], TextInput::new()))
     }
 
-    fn draw_name_popup(area: Rect) -> ((Rect, Block<'a>), (Rect, TextInput<T>)) {
+    fn draw_name_popup(area: Rect, title: &'a str, theme: &FileExplorerTheme) -> DecoratedInput<Block<'a>, T> {
         let area = crate::layout::popup::popup(
             area, Constraint::Percentage(40), Constraint::Length(3)
         );
@@ -166,34 +543,91 @@ impl<'a, T: TypingBehaviour> FileExplorerRenderer<'a, T> {
             Line::from(
                 vec![
                     " Cancel ".into(),
-                    "Esc".blue().bold(),
+                    Span::styled("Esc", theme.key_style()),
                 ]
             )
         );
         let block = Block::bordered()
-            .title(Title::from("Enter a Filename".bold()).alignment(Alignment::Center))
+            .title(Title::from(title.bold()).alignment(Alignment::Center))
             .title(instructions.alignment(Alignment::Center).position(Position::Bottom))
-            .border_set(border::THICK);
+            .border_set(theme.border_set());
         let input_field = TextInput::new();
         let inner = block.inner(area);
         ((area, block), (inner, input_field))
     }
 
+    fn draw_confirm_popup(area: Rect,
+                          title: &'a str,
+                          question: String,
+                          action: &'a str,
+                          keys: &'a str,
+                          theme: &FileExplorerTheme) -> (Rect, Paragraph<'a>) {
+        // Long names are wrapped, so grow the popup to fit the question
+        let width = (area.width * 2 / 5).saturating_sub(2).max(1) as usize;
+        let lines = question.chars().count().div_ceil(width) as u16;
+        let area = crate::layout::popup::popup(
+            area, Constraint::Percentage(40), Constraint::Length(lines.max(1) + 2)
+        );
+        let instructions = Title::from(
+            Line::from(
+                vec![
+                    action.into(),
+                    Span::styled(keys, theme.key_style()),
+                    " Cancel ".into(),
+                    Span::styled("n/Esc", theme.key_style()),
+                ]
+            )
+        );
+        let block = Block::bordered()
+            .title(Title::from(title.bold()).alignment(Alignment::Center))
+            .title(instructions.alignment(Alignment::Center).position(Position::Bottom))
+            .border_set(theme.border_set());
+        let paragraph = Paragraph::new(question)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(block);
+        (area, paragraph)
+    }
+
     pub fn render(self, buf: &mut Buffer, state: &mut FileExplorerState<T>) {
         let (area, block) = self.outer_block;
         block.render(area, buf);
         let (area, table) = self.table;
         <Table as StatefulWidget>::render(table, area, buf, &mut state.table_state);
-        let (area, status) = self.status;
-        status.render(area, buf);
+        if let Some((area, preview)) = self.preview {
+            preview.render(area, buf);
+        }
+        if let Some((area, list)) = self.sidebar {
+            let sidebar = state.sidebar.as_mut().unwrap();
+            <List as StatefulWidget>::render(list, area, buf, &mut sidebar.list_state);
+        }
+        if let Some(((area, label), (input_area, input))) = self.path {
+            label.render(area, buf);
+            <TextInput<T> as StatefulWidgetRef>::render_ref(
+                &input, input_area, buf, state.path_input_state.as_mut().unwrap()
+            );
+        } else {
+            let (area, status) = self.status;
+            status.render(area, buf);
+        }
+        if let Some(((area, label), (input_area, input))) = self.search {
+            label.render(area, buf);
+            <TextInput<T> as StatefulWidgetRef>::render_ref(
+                &input, input_area, buf, state.search_input_state.as_mut().unwrap()
+            );
+        }
         if let Some((block, input_box)) = self.input {
             let (area, block) = block;
             block.render(area, buf);
             let (area, input) = input_box;
             <TextInput<T> as StatefulWidgetRef>::render_ref(
-                &input, area, buf, &mut state.filename_input_state.as_mut().unwrap()
+                &input, area, buf, state.popup.as_mut().and_then(|popup| popup.input_mut()).unwrap()
             );
         }
+        if let Some((area, paragraph)) = self.confirm {
+            Clear.render(area, buf);
+            paragraph.render(area, buf);
+        }
     }
 }
 
@@ -202,10 +636,16 @@ impl<T: TypingBehaviour> StatefulWidgetRef for FileExplorer<T> {
     type State = FileExplorerState<T>;
 
     fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        FileExplorerRenderer::new(area, state.dialog_type, state).render(buf, state);
+        FileExplorerRenderer::new(area, self, state.dialog_type, state).render(buf, state);
     }
 }
 
 impl<T: TypingBehaviour> StatefulRemyWidget for FileExplorer<T> {
     type Input = FileExplorerState<T>;
 }
+
+impl<T: TypingBehaviour> CursorProvider for FileExplorer<T> {
+    fn cursor(&self, area: Rect, state: &mut Self::Input) -> Option<CursorPosition> {
+        FileExplorerRenderer::new(area, self, state.dialog_type, state).cursor()
+    }
+}
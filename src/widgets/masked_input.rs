@@ -0,0 +1,9 @@
+mod widget;
+mod input;
+mod state;
+
+
+pub use widget::MaskedInput;
+pub use state::MaskedInputState;
+pub use state::MaskedInputEvent;
+pub use input::DefaultMaskedInputInputConverter;
@@ -0,0 +1,20 @@
+use crate::events::Event;
+use crate::widgets::core::RemyWidgetCommandConverter;
+use crate::widgets::text_input::{DefaultTextInputInputConverter, TextInputAction, TextInputState};
+use crate::widgets::util::cursor::{CursorToCharIndexMapper, MaskedTypingBehaviour};
+use super::state::MaskedInputState;
+
+
+pub struct DefaultMaskedInputInputConverter;
+
+
+impl<T> RemyWidgetCommandConverter<MaskedInputState<T>> for DefaultMaskedInputInputConverter
+where
+    T: CursorToCharIndexMapper
+{
+    type Event = TextInputAction;
+
+    fn convert(event: Event, state: &MaskedInputState<T>) -> Option<Self::Event> {
+        <DefaultTextInputInputConverter as RemyWidgetCommandConverter<TextInputState<MaskedTypingBehaviour<T>>>>::convert(event, &state.0)
+    }
+}
@@ -0,0 +1,80 @@
+use arboard::Clipboard;
+
+use crate::widgets::core::{CursorShape, RemyWidgetState};
+use crate::widgets::text_input::{TextInputAction, TextInputEvent, TextInputState};
+use crate::widgets::util::cursor::{CursorToCharIndexMapper, InputMask, MaskedTypingBehaviour, TypingMode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaskedInputEvent {
+    Submitted { raw: String, formatted: String },
+    Incomplete,
+    Cancelled,
+    Typing
+}
+
+pub struct MaskedInputState<T: CursorToCharIndexMapper>(pub(super) TextInputState<MaskedTypingBehaviour<T>>);
+
+impl<T: CursorToCharIndexMapper> Default for MaskedInputState<T> {
+    fn default() -> Self {
+        Self(TextInputState::new(MaskedTypingBehaviour::default()))
+    }
+}
+
+
+impl<T: CursorToCharIndexMapper> MaskedInputState<T> {
+    pub fn new(mask: InputMask, clipboard: Clipboard) -> Self {
+        Self(TextInputState::new(MaskedTypingBehaviour::new(mask, clipboard)))
+    }
+
+    pub fn raw_value(&self) -> String {
+        self.0.behaviour().raw_value()
+    }
+
+    pub fn formatted_value(&self) -> String {
+        self.0.behaviour().formatted_value()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.0.behaviour().is_complete()
+    }
+
+    pub fn set_value(&mut self, value: String) {
+        self.0.set_text(value)
+    }
+
+    pub fn get_cursor_location(&mut self, width: usize) -> usize {
+        self.0.get_cursor_location(width)
+    }
+
+    pub fn get_visible_text(&mut self, width: usize) -> (String, Option<(usize, usize)>) {
+        self.0.get_visible_text(width)
+    }
+
+    pub fn typing_mode(&self) -> TypingMode {
+        self.0.typing_mode()
+    }
+
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.0.cursor_shape()
+    }
+}
+
+
+impl<T: CursorToCharIndexMapper> RemyWidgetState for MaskedInputState<T> {
+    type Command = TextInputAction;
+    // Fails if the clipboard can't be used
+    type EventOutput = anyhow::Result<MaskedInputEvent>;
+
+    fn handle_native_event(&mut self, event: Option<Self::Command>) -> Self::EventOutput {
+        let event = match self.0.handle_native_event(event)? {
+            TextInputEvent::Submitted(_) if self.is_complete() => MaskedInputEvent::Submitted {
+                raw: self.raw_value(),
+                formatted: self.formatted_value()
+            },
+            TextInputEvent::Submitted(_) => MaskedInputEvent::Incomplete,
+            TextInputEvent::Cancelled => MaskedInputEvent::Cancelled,
+            TextInputEvent::Typing => MaskedInputEvent::Typing
+        };
+        Ok(event)
+    }
+}
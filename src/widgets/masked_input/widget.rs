@@ -0,0 +1,62 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::prelude::Style;
+use ratatui::widgets::{Block, StatefulWidgetRef};
+use crate::widgets::core::{CursorPosition, CursorProvider, StatefulRemyWidget};
+use crate::widgets::masked_input::state::MaskedInputState;
+use crate::widgets::text_input::TextInput;
+use crate::widgets::util::cursor::{CursorToCharIndexMapper, MaskedTypingBehaviour};
+
+pub struct MaskedInput<T: CursorToCharIndexMapper>(TextInput<MaskedTypingBehaviour<T>>);
+
+
+impl<T: CursorToCharIndexMapper> MaskedInput<T> {
+    pub fn new() -> Self {
+        Self(TextInput::new())
+    }
+
+    pub fn with_style(self, style: Style) -> Self {
+        Self(self.0.with_style(style))
+    }
+
+    pub fn with_selection_style(self, style: Style) -> Self {
+        Self(self.0.with_selection_style(style))
+    }
+
+    pub fn with_overwrite_style(self, style: Style) -> Self {
+        Self(self.0.with_overwrite_style(style))
+    }
+
+    pub fn with_alignment(self, alignment: Alignment) -> Self {
+        Self(self.0.with_alignment(alignment))
+    }
+
+    pub fn with_block(self, block: Block<'static>) -> Self {
+        Self(self.0.with_block(block))
+    }
+}
+
+impl<T: CursorToCharIndexMapper> Default for MaskedInput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl<T: CursorToCharIndexMapper> StatefulWidgetRef for MaskedInput<T> {
+    type State = MaskedInputState<T>;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.0.render_ref(area, buf, &mut state.0)
+    }
+}
+
+impl<T: CursorToCharIndexMapper> StatefulRemyWidget for MaskedInput<T> {
+    type Input = MaskedInputState<T>;
+}
+
+impl<T: CursorToCharIndexMapper> CursorProvider for MaskedInput<T> {
+    fn cursor(&self, area: Rect, state: &mut Self::Input) -> Option<CursorPosition> {
+        self.0.cursor(area, &mut state.0)
+    }
+}
@@ -0,0 +1,12 @@
+mod widget;
+mod input;
+mod state;
+
+
+pub use widget::NumberInput;
+pub use state::NumberInputState;
+pub use state::NumberInputEvent;
+pub use state::NumberInputError;
+pub use state::InputNumber;
+pub use input::NumberInputAction;
+pub use input::DefaultNumberInputInputConverter;
@@ -0,0 +1,52 @@
+use crate::events::{Event, Key};
+use crate::widgets::core::RemyWidgetCommandConverter;
+use crate::widgets::text_input::{DefaultTextInputInputConverter, TextInputAction, TextInputState};
+use crate::widgets::util::cursor::TypingBehaviour;
+use super::state::{InputNumber, NumberInputState};
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberInputAction {
+    Increment,
+    Decrement,
+    PageIncrement,
+    PageDecrement,
+    Input(TextInputAction)
+}
+
+
+pub struct DefaultNumberInputInputConverter;
+
+
+impl<T, N> RemyWidgetCommandConverter<NumberInputState<T, N>> for DefaultNumberInputInputConverter
+where
+    T: TypingBehaviour,
+    N: InputNumber
+{
+    type Event = NumberInputAction;
+
+    fn convert(event: Event, state: &NumberInputState<T, N>) -> Option<Self::Event> {
+        match event {
+            Event::KeyPress { key, ctrl, .. } => {
+                match key {
+                    Key::ArrowUp => Some(NumberInputAction::Increment),
+                    Key::ArrowDown => Some(NumberInputAction::Decrement),
+                    Key::PageUp => Some(NumberInputAction::PageIncrement),
+                    Key::PageDown => Some(NumberInputAction::PageDecrement),
+                    Key::Character(c) if !ctrl && !is_number_character::<N>(c) => None,
+                    _ => <DefaultTextInputInputConverter as RemyWidgetCommandConverter<TextInputState<T>>>::convert(event, &state.input)
+                        .map(NumberInputAction::Input)
+                }
+            }
+            Event::Null => None
+        }
+    }
+}
+
+fn is_number_character<N: InputNumber>(c: char) -> bool {
+    match c {
+        '0'..='9' | '-' | '+' => true,
+        '.' | ',' => N::ALLOWS_FRACTION,
+        _ => false
+    }
+}
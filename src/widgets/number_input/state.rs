@@ -0,0 +1,218 @@
+use std::fmt::Display;
+
+use crate::widgets::core::{CursorShape, RemyWidgetState};
+use crate::widgets::number_input::input::NumberInputAction;
+use crate::widgets::text_input::{TextInputEvent, TextInputState};
+use crate::widgets::util::cursor::{TypingBehaviour, TypingMode};
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Number Types
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub trait InputNumber: Copy + PartialOrd + Default + Display + 'static {
+    const ALLOWS_FRACTION: bool;
+    const DEFAULT_STEP: Self;
+
+    fn parse(text: &str) -> Option<Self>;
+
+    fn add_steps(self, step: Self, count: i64) -> Self;
+}
+
+impl InputNumber for i64 {
+    const ALLOWS_FRACTION: bool = false;
+    const DEFAULT_STEP: Self = 1;
+
+    fn parse(text: &str) -> Option<Self> {
+        text.trim().parse().ok()
+    }
+
+    fn add_steps(self, step: Self, count: i64) -> Self {
+        self.saturating_add(step.saturating_mul(count))
+    }
+}
+
+impl InputNumber for f64 {
+    const ALLOWS_FRACTION: bool = true;
+    const DEFAULT_STEP: Self = 1.0;
+
+    fn parse(text: &str) -> Option<Self> {
+        text.trim()
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+    }
+
+    // Rounded to the precision of the value and the step, so that e.g. stepping
+    // by 0.1 gives 0.3 rather than 0.30000000000000004
+    fn add_steps(self, step: Self, count: i64) -> Self {
+        let precision = decimal_places(self).max(decimal_places(step));
+        let value = self + step * count as f64;
+        let rounded = format!("{:.*}", precision, value).parse().unwrap_or(value);
+        // Adding zero turns a rounded -0 into 0
+        rounded + 0.0
+    }
+}
+
+// Number of digits after the decimal point in the shortest representation
+fn decimal_places(value: f64) -> usize {
+    value.to_string()
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Events
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NumberInputError {
+    NotANumber,
+    BelowMinimum,
+    AboveMaximum
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NumberInputEvent<N: InputNumber> {
+    Submitted(N),
+    Invalid(NumberInputError),
+    Cancelled,
+    Typing
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// State
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+const PAGE_STEPS: i64 = 10;
+
+pub struct NumberInputState<T: TypingBehaviour, N: InputNumber = i64> {
+    pub(super) input: TextInputState<T>,
+    min: Option<N>,
+    max: Option<N>,
+    step: N
+}
+
+impl<T: TypingBehaviour, N: InputNumber> Default for NumberInputState<T, N> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: TypingBehaviour, N: InputNumber> NumberInputState<T, N> {
+    pub fn new(behaviour: T) -> Self {
+        Self {
+            input: TextInputState::new(behaviour),
+            min: None,
+            max: None,
+            step: N::DEFAULT_STEP
+        }
+    }
+
+    pub fn with_min(mut self, min: N) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: N) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn with_step(mut self, step: N) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_value(mut self, value: N) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    pub fn value(&self) -> Result<N, NumberInputError> {
+        let value = N::parse(self.input.text())
+            .ok_or(NumberInputError::NotANumber)?;
+        if self.min.is_some_and(|min| value < min) {
+            Err(NumberInputError::BelowMinimum)
+        } else if self.max.is_some_and(|max| value > max) {
+            Err(NumberInputError::AboveMaximum)
+        } else {
+            Ok(value)
+        }
+    }
+
+    pub fn set_value(&mut self, value: N) {
+        self.input.set_text(self.clamp(value).to_string());
+    }
+
+    pub fn get_cursor_location(&mut self, width: usize) -> usize {
+        self.input.get_cursor_location(width)
+    }
+
+    pub fn get_visible_text(&mut self, width: usize) -> (String, Option<(usize, usize)>) {
+        self.input.get_visible_text(width)
+    }
+
+    pub fn text(&self) -> &str {
+        self.input.text()
+    }
+
+    pub fn typing_mode(&self) -> TypingMode {
+        self.input.typing_mode()
+    }
+
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.input.cursor_shape()
+    }
+
+    fn clamp(&self, mut value: N) -> N {
+        if let Some(min) = self.min {
+            if value < min {
+                value = min;
+            }
+        }
+        if let Some(max) = self.max {
+            if value > max {
+                value = max;
+            }
+        }
+        value
+    }
+
+    fn change_value(&mut self, count: i64) {
+        let current = N::parse(self.input.text())
+            .unwrap_or_else(|| self.clamp(N::default()));
+        self.set_value(current.add_steps(self.step, count));
+    }
+}
+
+
+impl<B: TypingBehaviour, N: InputNumber> RemyWidgetState for NumberInputState<B, N> {
+    type Command = NumberInputAction;
+    // Fails if the clipboard can't be used
+    type EventOutput = anyhow::Result<NumberInputEvent<N>>;
+
+    fn handle_native_event(&mut self, event: Option<Self::Command>) -> Self::EventOutput {
+        match event {
+            Some(NumberInputAction::Increment) => self.change_value(1),
+            Some(NumberInputAction::Decrement) => self.change_value(-1),
+            Some(NumberInputAction::PageIncrement) => self.change_value(PAGE_STEPS),
+            Some(NumberInputAction::PageDecrement) => self.change_value(-PAGE_STEPS),
+            Some(NumberInputAction::Input(inner)) => {
+                return Ok(match self.input.handle_native_event(Some(inner))? {
+                    TextInputEvent::Submitted(_) => match self.value() {
+                        Ok(value) => NumberInputEvent::Submitted(value),
+                        Err(e) => NumberInputEvent::Invalid(e)
+                    }
+                    TextInputEvent::Cancelled => NumberInputEvent::Cancelled,
+                    TextInputEvent::Typing => NumberInputEvent::Typing
+                });
+            }
+            None => ()
+        }
+        Ok(NumberInputEvent::Typing)
+    }
+}
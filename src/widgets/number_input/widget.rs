@@ -0,0 +1,70 @@
+use std::marker::PhantomData;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::prelude::Style;
+use ratatui::widgets::{Block, StatefulWidgetRef};
+use crate::widgets::core::{CursorPosition, CursorProvider, StatefulRemyWidget};
+use crate::widgets::number_input::state::{InputNumber, NumberInputState};
+use crate::widgets::text_input::TextInput;
+use crate::widgets::util::cursor::TypingBehaviour;
+
+pub struct NumberInput<T: TypingBehaviour, N: InputNumber = i64> {
+    input: TextInput<T>,
+    _number: PhantomData<N>
+}
+
+
+impl<T: TypingBehaviour, N: InputNumber> NumberInput<T, N> {
+    pub fn new() -> Self {
+        Self {
+            input: TextInput::new().with_alignment(Alignment::Right),
+            _number: PhantomData
+        }
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.input = self.input.with_style(style);
+        self
+    }
+
+    pub fn with_selection_style(mut self, style: Style) -> Self {
+        self.input = self.input.with_selection_style(style);
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.input = self.input.with_alignment(alignment);
+        self
+    }
+
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.input = self.input.with_block(block);
+        self
+    }
+}
+
+impl<T: TypingBehaviour, N: InputNumber> Default for NumberInput<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl<T: TypingBehaviour, N: InputNumber> StatefulWidgetRef for NumberInput<T, N> {
+    type State = NumberInputState<T, N>;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.input.render_ref(area, buf, &mut state.input)
+    }
+}
+
+impl<T: TypingBehaviour, N: InputNumber> StatefulRemyWidget for NumberInput<T, N> {
+    type Input = NumberInputState<T, N>;
+}
+
+impl<T: TypingBehaviour, N: InputNumber> CursorProvider for NumberInput<T, N> {
+    fn cursor(&self, area: Rect, state: &mut Self::Input) -> Option<CursorPosition> {
+        self.input.cursor(area, &mut state.input)
+    }
+}
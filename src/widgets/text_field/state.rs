@@ -1,5 +1,5 @@
-use crate::widgets::core::RemyWidgetState;
-use crate::widgets::util::cursor::{TypingBehaviour, UserAction};
+use crate::widgets::core::{CursorShape, RemyWidgetState};
+use crate::widgets::util::cursor::{TypingBehaviour, TypingMode, UserAction};
 
 #[derive(Debug, Default, Clone)]
 pub struct TextFieldState<T: TypingBehaviour>(T);
@@ -9,6 +9,14 @@ impl<T: TypingBehaviour> TextFieldState<T> {
         Self(behaviour)
     }
 
+    pub fn behaviour(&self) -> &T {
+        &self.0
+    }
+
+    pub fn behaviour_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
     pub fn get_cursor_location(&mut self, width: usize) -> usize {
         self.0.get_cursor_position(width)
     }
@@ -17,19 +25,36 @@ impl<T: TypingBehaviour> TextFieldState<T> {
         self.0.get_visible_text(width)
     }
 
+    pub fn get_overflow(&mut self, width: usize) -> (bool, bool) {
+        self.0.get_overflow(width)
+    }
+
     pub fn text(&self) -> &str {
         self.0.get_text()
     }
+
+    pub fn set_text(&mut self, text: String) {
+        self.0.set_text(text)
+    }
+
+    pub fn typing_mode(&self) -> TypingMode {
+        self.0.typing_mode()
+    }
+
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.0.cursor_shape()
+    }
 }
 
 
 impl<B: TypingBehaviour> RemyWidgetState for TextFieldState<B> {
     type Command = UserAction;
-    type EventOutput = ();
+    type EventOutput = anyhow::Result<()>;
 
     fn handle_native_event(&mut self, event: Option<Self::Command>) -> Self::EventOutput {
-        if let Some(action) = event {
-            self.0.handle_user_action(action);
+        match event {
+            Some(action) => self.0.handle_user_action(action),
+            None => Ok(())
         }
     }
 }
@@ -1,24 +1,44 @@
 use std::marker::PhantomData;
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
-use ratatui::style::Style;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{StatefulWidgetRef, Widget};
-use crate::widgets::core::StatefulRemyWidget;
+use ratatui::widgets::{Block, StatefulWidgetRef, Widget, WidgetRef};
+use unicode_width::UnicodeWidthStr;
+use crate::widgets::core::{CursorPosition, CursorProvider, StatefulRemyWidget};
 use crate::widgets::text_field::state::TextFieldState;
-use crate::widgets::util::cursor::TypingBehaviour;
+use crate::widgets::util::cursor::{TypingBehaviour, TypingMode};
 
 pub struct TextField<T: TypingBehaviour> {
     normal_style: Option<Style>,
     selection_style: Option<Style>,
+    overwrite_style: Option<Style>,
+    overflow_markers: Option<(String, String)>,
+    overflow_style: Option<Style>,
+    alignment: Alignment,
+    padding: u16,
+    block: Option<Block<'static>>,
     _behaviour: PhantomData<T>
 }
 
+struct TextFieldLayout {
+    text_area: Rect,
+    left_marker: Option<Rect>,
+    right_marker: Option<Rect>,
+    text_width: usize
+}
+
 impl<T: TypingBehaviour> TextField<T> {
     pub fn new() -> Self {
         Self {
             normal_style: None,
             selection_style: None,
+            overwrite_style: None,
+            overflow_markers: None,
+            overflow_style: None,
+            alignment: Alignment::Left,
+            padding: 0,
+            block: None,
             _behaviour: PhantomData
         }
     }
@@ -33,12 +53,95 @@ impl<T: TypingBehaviour> TextField<T> {
         self
     }
 
+    pub fn with_overwrite_style(mut self, style: Style) -> Self {
+        self.overwrite_style = Some(style);
+        self
+    }
+
+    pub fn with_overflow_markers(mut self, left: &str, right: &str) -> Self {
+        self.overflow_markers = Some((left.to_string(), right.to_string()));
+        self
+    }
+
+    pub fn with_overflow_style(mut self, style: Style) -> Self {
+        self.overflow_style = Some(style);
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_horizontal_padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
     pub fn with_style_and_inverted_selection(self, s: Style) -> Self {
         let fg = s.bg.unwrap_or_else(|| Style::default().bg.unwrap());
         let bg = s.fg.unwrap_or_else(|| Style::default().fg.unwrap());
         self.with_style(s)
             .with_selection_style(Style::default().fg(fg).bg(bg))
     }
+
+    fn layout(&self, area: Rect, state: &mut TextFieldState<T>) -> TextFieldLayout {
+        let mut area = match self.block.as_ref() {
+            Some(block) => block.inner(area),
+            None => area
+        };
+        let padding = self.padding.min(area.width / 2);
+        area.x += padding;
+        area.width -= 2 * padding;
+        let full_width = area.width as usize;
+        let mut layout = TextFieldLayout {
+            text_area: area,
+            left_marker: None,
+            right_marker: None,
+            text_width: full_width
+        };
+        if let Some((left, right)) = self.overflow_markers.as_ref() {
+            let (left_width, right_width) = (left.width(), right.width());
+            if state.text().width() >= full_width && full_width > left_width + right_width {
+                // Space for the markers is always reserved, so that the 
+                // markers never cover the cursor.
+                let text_width = full_width - left_width - right_width;
+                let (hidden_left, hidden_right) = state.get_overflow(text_width);
+                let mut x = area.x;
+                if hidden_left {
+                    layout.left_marker = Some(Rect { width: left_width as u16, ..area });
+                    x += left_width as u16;
+                }
+                if hidden_right {
+                    layout.right_marker = Some(Rect {
+                        x: area.right() - right_width as u16,
+                        width: right_width as u16,
+                        ..area
+                    });
+                }
+                layout.text_area = Rect { x, width: text_width as u16, ..area };
+                layout.text_width = text_width;
+                return layout;
+            }
+        }
+        let text_width = state.text().width();
+        if text_width < full_width {
+            // One column is reserved for the cursor at the end of the text 
+            let free = (full_width - text_width - 1) as u16;
+            let offset = match self.alignment {
+                Alignment::Left => 0,
+                Alignment::Center => free / 2,
+                Alignment::Right => free
+            };
+            layout.text_area = Rect { x: area.x + offset, width: area.width - offset, ..area };
+        }
+        layout
+    }
 }
 
 
@@ -48,7 +151,11 @@ impl<T: TypingBehaviour> StatefulWidgetRef for TextField<T> {
     type State = TextFieldState<T>;
 
     fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let width = area.width as usize;
+        if let Some(block) = self.block.as_ref() {
+            block.render_ref(area, buf);
+        }
+        let layout = self.layout(area, state);
+        let (area, width) = (layout.text_area, layout.text_width);
         let (text, selection) = state.get_visible_text(width);
         let formatted = if let Some((start, end)) = selection {
             let first = &text[0..start];
@@ -72,9 +179,38 @@ impl<T: TypingBehaviour> StatefulWidgetRef for TextField<T> {
             }
         };
         formatted.render(area, buf);
+        if state.typing_mode() == TypingMode::Overwrite {
+            // Draw a block over the character which will be overwritten 
+            let cursor = state.get_cursor_location(width) as u16;
+            if cursor < area.width {
+                let style = self.overwrite_style.unwrap_or(Style::new().reversed());
+                buf.set_style(Rect { x: area.x + cursor, y: area.y, width: 1, height: 1 }, style);
+            }
+        }
+        if let Some((left, right)) = self.overflow_markers.as_ref() {
+            let style = self.overflow_style.unwrap_or_default();
+            if let Some(marker_area) = layout.left_marker {
+                Span::styled(left.as_str(), style).render(marker_area, buf);
+            }
+            if let Some(marker_area) = layout.right_marker {
+                Span::styled(right.as_str(), style).render(marker_area, buf);
+            }
+        }
     }
 }
 
 impl<T: TypingBehaviour> StatefulRemyWidget for TextField<T> {
     type Input = TextFieldState<T>;
 }
+
+impl<T: TypingBehaviour> CursorProvider for TextField<T> {
+    fn cursor(&self, area: Rect, state: &mut Self::Input) -> Option<CursorPosition> {
+        let layout = self.layout(area, state);
+        let offset = state.get_cursor_location(layout.text_width) as u16;
+        Some(CursorPosition {
+            x: layout.text_area.x + offset,
+            y: layout.text_area.y,
+            shape: state.cursor_shape()
+        })
+    }
+}
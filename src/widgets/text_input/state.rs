@@ -1,7 +1,7 @@
-use crate::widgets::core::RemyWidgetState;
+use crate::widgets::core::{CursorShape, RemyWidgetState};
 use crate::widgets::text_field::TextFieldState;
 use crate::widgets::text_input::input::TextInputAction;
-use crate::widgets::util::cursor::TypingBehaviour;
+use crate::widgets::util::cursor::{TypingBehaviour, TypingMode};
 
 #[derive(Debug, Default, Clone)]
 pub struct TextInputState<T: TypingBehaviour>(pub(super) TextFieldState<T>);
@@ -18,6 +18,14 @@ impl<T: TypingBehaviour> TextInputState<T> {
         Self(TextFieldState::new(behaviour))
     }
 
+    pub fn behaviour(&self) -> &T {
+        self.0.behaviour()
+    }
+
+    pub fn behaviour_mut(&mut self) -> &mut T {
+        self.0.behaviour_mut()
+    }
+
     pub fn get_cursor_location(&mut self, width: usize) -> usize {
         self.0.get_cursor_location(width)
     }
@@ -26,28 +34,42 @@ impl<T: TypingBehaviour> TextInputState<T> {
         self.0.get_visible_text(width)
     }
 
-    fn text(&self) -> &str {
+    pub fn text(&self) -> &str {
         self.0.text()
     }
+
+    pub fn set_text(&mut self, text: String) {
+        self.0.set_text(text)
+    }
+
+    pub fn typing_mode(&self) -> TypingMode {
+        self.0.typing_mode()
+    }
+
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.0.cursor_shape()
+    }
 }
 
 
 impl<B: TypingBehaviour> RemyWidgetState for TextInputState<B> {
     type Command = TextInputAction;
-    type EventOutput = TextInputEvent;
+    // Fails if the clipboard can't be used
+    type EventOutput = anyhow::Result<TextInputEvent>;
 
     fn handle_native_event(&mut self, event: Option<Self::Command>) -> Self::EventOutput {
-        match event {
+        let event = match event {
             Some(inner) => match inner {
                 TextInputAction::Esc => TextInputEvent::Cancelled,
                 TextInputAction::Enter => TextInputEvent::Submitted(self.0.text().to_string()),
                 TextInputAction::Other(a) => {
-                    let _ = self.0.handle_native_event(Some(a));
+                    self.0.handle_native_event(Some(a))?;
                     TextInputEvent::Typing
                 }
                 TextInputAction::Null => TextInputEvent::Typing,
             }
             None => TextInputEvent::Typing
-        }
+        };
+        Ok(event)
     }
 }
@@ -1,8 +1,8 @@
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Alignment, Rect};
 use ratatui::prelude::Style;
-use ratatui::widgets::StatefulWidgetRef;
-use crate::widgets::core::StatefulRemyWidget;
+use ratatui::widgets::{Block, StatefulWidgetRef};
+use crate::widgets::core::{CursorPosition, CursorProvider, StatefulRemyWidget};
 use crate::widgets::text_field::TextField;
 use crate::widgets::text_input::state::TextInputState;
 use crate::widgets::util::cursor::TypingBehaviour;
@@ -23,6 +23,30 @@ impl<T: TypingBehaviour> TextInput<T> {
         Self(self.0.with_selection_style(style))
     }
 
+    pub fn with_overwrite_style(self, style: Style) -> Self {
+        Self(self.0.with_overwrite_style(style))
+    }
+
+    pub fn with_overflow_markers(self, left: &str, right: &str) -> Self {
+        Self(self.0.with_overflow_markers(left, right))
+    }
+
+    pub fn with_overflow_style(self, style: Style) -> Self {
+        Self(self.0.with_overflow_style(style))
+    }
+
+    pub fn with_alignment(self, alignment: Alignment) -> Self {
+        Self(self.0.with_alignment(alignment))
+    }
+
+    pub fn with_horizontal_padding(self, padding: u16) -> Self {
+        Self(self.0.with_horizontal_padding(padding))
+    }
+
+    pub fn with_block(self, block: Block<'static>) -> Self {
+        Self(self.0.with_block(block))
+    }
+
     pub fn with_style_and_inverted_selection(self, s: Style) -> Self {
         Self(self.0.with_style_and_inverted_selection(s))
     }
@@ -40,3 +64,9 @@ impl<T: TypingBehaviour> StatefulWidgetRef for TextInput<T> {
 impl<T: TypingBehaviour> StatefulRemyWidget for TextInput<T> {
     type Input = TextInputState<T>;
 }
+
+impl<T: TypingBehaviour> CursorProvider for TextInput<T> {
+    fn cursor(&self, area: Rect, state: &mut Self::Input) -> Option<CursorPosition> {
+        self.0.cursor(area, &mut state.0)
+    }
+}
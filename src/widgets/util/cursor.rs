@@ -1,6 +1,7 @@
 mod cursor;
 mod typing;
 mod actions;
+mod masked;
 
 
 pub use actions::UserAction;
@@ -8,3 +9,7 @@ pub use cursor::CursorToCharIndexMapper;
 pub use cursor::SimpleCursorToCharIndexMapper;
 pub use typing::TypingBehaviour;
 pub use typing::DefaultTypingBehaviour;
+pub use typing::TypingMode;
+pub use masked::InputMask;
+pub use masked::MaskSlot;
+pub use masked::MaskedTypingBehaviour;
\ No newline at end of file
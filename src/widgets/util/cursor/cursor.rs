@@ -4,6 +4,15 @@ use unicode_width::UnicodeWidthStr;
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CursorCoordinate(usize);
 
+impl CursorCoordinate {
+    pub(super) fn new(index: usize) -> Self {
+        Self(index)
+    }
+    
+    pub(super) fn index(&self) -> usize {
+        self.0
+    }
+}
 
 
 pub struct Cursor<T: CursorToCharIndexMapper> {
@@ -77,6 +86,16 @@ impl<T: CursorToCharIndexMapper> Cursor<T> {
         (self.text[start..stop].to_string(), cursor, mapped_selection)
     }
     
+    pub fn get_overflow(&mut self, width: usize) -> (bool, bool) {
+        let window = T::get_text_window(
+            &self.text, self.left_window_pos, self.position, width
+        );
+        self.left_window_pos = window.cursor_start;
+        let hidden_left = window.cursor_start.0 > 0;
+        let hidden_right = window.cursor_end.0 < T::string_length(&self.text);
+        (hidden_left, hidden_right)
+    }
+    
     // Movement 
     
     pub fn move_left(&mut self) -> bool {
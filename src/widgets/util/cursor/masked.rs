@@ -0,0 +1,398 @@
+use arboard::Clipboard;
+use super::cursor::{Cursor, CursorCoordinate, CursorToCharIndexMapper};
+use super::actions::UserAction;
+use super::typing::{clipboard, TypingBehaviour, TypingMode};
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Mask
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MaskSlot {
+    Digit,
+    Letter,
+    Alphanumeric,
+    Any,
+    Literal(char)
+}
+
+impl MaskSlot {
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Letter => c.is_ascii_alphabetic(),
+            MaskSlot::Alphanumeric => c.is_ascii_alphanumeric(),
+            MaskSlot::Any => c.is_ascii_graphic() || c == ' ',
+            MaskSlot::Literal(_) => false
+        }
+    }
+
+    pub fn is_literal(&self) -> bool {
+        matches!(self, MaskSlot::Literal(_))
+    }
+}
+
+
+/// Input mask such as `####-##-##`.
+///
+/// `#` accepts a digit, `A` a letter, `X` a letter or digit, and `*` any
+/// printable character. All other characters are literals; `\` turns
+/// the next character into a literal.
+///
+/// Masks are limited to ASCII, since every slot must be a single byte
+/// of the underlying text. Other characters are never accepted as input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMask {
+    slots: Vec<MaskSlot>,
+    placeholder: char
+}
+
+impl InputMask {
+    /// # Panics
+    ///
+    /// Panics if the pattern contains a character which is not ASCII.
+    pub fn new(pattern: &str) -> Self {
+        assert!(pattern.is_ascii(), "Input mask must be ASCII: {:?}", pattern);
+        let mut slots = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let slot = match c {
+                '#' => MaskSlot::Digit,
+                'A' => MaskSlot::Letter,
+                'X' => MaskSlot::Alphanumeric,
+                '*' => MaskSlot::Any,
+                '\\' => MaskSlot::Literal(chars.next().unwrap_or('\\')),
+                other => MaskSlot::Literal(other)
+            };
+            slots.push(slot);
+        }
+        Self { slots, placeholder: '_' }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the placeholder is not ASCII.
+    pub fn with_placeholder(mut self, placeholder: char) -> Self {
+        assert!(placeholder.is_ascii(), "Placeholder must be ASCII: {:?}", placeholder);
+        self.placeholder = placeholder;
+        self
+    }
+
+    pub fn slots(&self) -> &[MaskSlot] {
+        &self.slots
+    }
+
+    fn template(&self) -> String {
+        self.slots.iter()
+            .map(|slot| match slot {
+                MaskSlot::Literal(c) => *c,
+                _ => self.placeholder
+            })
+            .collect()
+    }
+}
+
+impl Default for InputMask {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Typing Behaviour
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub struct MaskedTypingBehaviour<T: CursorToCharIndexMapper> {
+    cursor: Cursor<T>,
+    mask: InputMask,
+    filled: Vec<bool>,
+    selection: Option<(CursorCoordinate, CursorCoordinate)>,
+    // Created when first used
+    clipboard: Option<Clipboard>
+}
+
+impl<T: CursorToCharIndexMapper> MaskedTypingBehaviour<T> {
+    pub fn new(mask: InputMask, clipboard: Clipboard) -> Self {
+        Self::with_clipboard(mask, Some(clipboard))
+    }
+
+    fn with_clipboard(mask: InputMask, clipboard: Option<Clipboard>) -> Self {
+        let mut behaviour = Self {
+            cursor: Cursor::new(mask.template()),
+            filled: vec![false; mask.slots.len()],
+            mask,
+            selection: None,
+            clipboard
+        };
+        behaviour.cursor.set_position(behaviour.next_slot(0));
+        behaviour
+    }
+
+    pub fn mask(&self) -> &InputMask {
+        &self.mask
+    }
+
+    /// The characters entered in the editable slots, without literals.
+    pub fn raw_value(&self) -> String {
+        self.editable_slots()
+            .filter(|index| self.filled[*index])
+            .map(|index| self.char_at(index))
+            .collect()
+    }
+
+    /// The entered text including literals, up to the last filled slot.
+    pub fn formatted_value(&self) -> String {
+        match self.filled.iter().rposition(|filled| *filled) {
+            Some(last) => self.cursor
+                .get_substring(CursorCoordinate::new(0), CursorCoordinate::new(last + 1))
+                .to_string(),
+            None => String::new()
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.editable_slots().all(|index| self.filled[index])
+    }
+
+    fn editable_slots(&self) -> impl Iterator<Item=usize> + '_ {
+        self.mask.slots.iter()
+            .enumerate()
+            .filter(|(_, slot)| !slot.is_literal())
+            .map(|(index, _)| index)
+    }
+
+    fn char_at(&self, index: usize) -> char {
+        self.cursor
+            .get_substring(CursorCoordinate::new(index), CursorCoordinate::new(index + 1))
+            .chars()
+            .next()
+            .expect("Index out of bounds")
+    }
+
+    fn position(&self) -> usize {
+        self.cursor.get_position().index()
+    }
+
+    // First editable slot at or after the given index, or the end of the mask.
+    fn next_slot(&self, from: usize) -> CursorCoordinate {
+        let index = (from..self.mask.slots.len())
+            .find(|index| !self.mask.slots[*index].is_literal())
+            .unwrap_or(self.mask.slots.len());
+        CursorCoordinate::new(index)
+    }
+
+    // Last editable slot before the given index.
+    fn previous_slot(&self, before: usize) -> Option<CursorCoordinate> {
+        (0..before)
+            .rev()
+            .find(|index| !self.mask.slots[*index].is_literal())
+            .map(CursorCoordinate::new)
+    }
+
+    fn set_slot(&mut self, index: usize, c: Option<char>) {
+        let position = self.cursor.get_position();
+        self.cursor.set_position(CursorCoordinate::new(index));
+        self.cursor.replace_char_at_cursor(c.unwrap_or(self.mask.placeholder));
+        self.cursor.set_position(position);
+        self.filled[index] = c.is_some();
+    }
+
+    fn clear_selection(&mut self) -> bool {
+        match self.selection.take() {
+            None => false,
+            Some((start, stop)) => {
+                for index in start.index()..stop.index() {
+                    if !self.mask.slots[index].is_literal() {
+                        self.set_slot(index, None);
+                    }
+                }
+                self.cursor.set_position(self.next_slot(start.index()));
+                true
+            }
+        }
+    }
+
+    fn action_typing(&mut self, c: char) {
+        self.clear_selection();
+        let position = self.position();
+        if self.mask.slots.get(position) == Some(&MaskSlot::Literal(c)) {
+            // Typing a literal explicitly moves past it
+            self.cursor.set_position(self.next_slot(position + 1));
+            return;
+        }
+        let slot = self.next_slot(position).index();
+        if self.mask.slots.get(slot).is_some_and(|s| s.accepts(c)) {
+            self.set_slot(slot, Some(c));
+            self.cursor.set_position(self.next_slot(slot + 1));
+            return;
+        }
+        // Typing a separator skips to the group after it, e.g. `.` in an IP address
+        let separator = (position..self.mask.slots.len())
+            .find(|index| self.mask.slots[*index] == MaskSlot::Literal(c));
+        if let Some(separator) = separator {
+            self.cursor.set_position(self.next_slot(separator + 1));
+        }
+    }
+
+    fn action_backspace(&mut self) {
+        if self.clear_selection() {
+            return;
+        }
+        if let Some(slot) = self.previous_slot(self.position()) {
+            self.set_slot(slot.index(), None);
+            self.cursor.set_position(slot);
+        }
+    }
+
+    fn action_delete(&mut self) {
+        if self.clear_selection() {
+            return;
+        }
+        let slot = self.next_slot(self.position());
+        if slot.index() < self.mask.slots.len() {
+            self.set_slot(slot.index(), None);
+            self.cursor.set_position(slot);
+        }
+    }
+
+    fn action_cursor_left(&mut self, select: bool) {
+        let previous = self.cursor.get_position();
+        if let Some(slot) = self.previous_slot(previous.index()) {
+            self.cursor.set_position(slot);
+        }
+        self.update_selection(previous, select);
+    }
+
+    fn action_cursor_right(&mut self, select: bool) {
+        let previous = self.cursor.get_position();
+        let slot = self.next_slot(previous.index());
+        let next = if slot.index() < self.mask.slots.len() {
+            self.next_slot(slot.index() + 1)
+        } else {
+            slot
+        };
+        self.cursor.set_position(next);
+        self.update_selection(previous, select);
+    }
+
+    fn action_to_start(&mut self, select: bool) {
+        let previous = self.cursor.get_position();
+        self.cursor.set_position(self.next_slot(0));
+        self.update_selection(previous, select);
+    }
+
+    fn action_to_end(&mut self, select: bool) {
+        let previous = self.cursor.get_position();
+        self.cursor.move_to_end();
+        self.update_selection(previous, select);
+    }
+
+    fn action_select_all(&mut self) {
+        self.cursor.move_to_end();
+        self.selection = Some((CursorCoordinate::new(0), self.cursor.get_position()))
+            .filter(|(start, stop)| start != stop);
+    }
+
+    fn update_selection(&mut self, previous: CursorCoordinate, select: bool) {
+        if !select {
+            self.selection = None;
+            return;
+        }
+        let current = self.cursor.get_position();
+        let (start, stop) = match self.selection {
+            None => (previous.min(current), previous.max(current)),
+            Some((start, stop)) if previous == start => (current.min(stop), current.max(stop)),
+            Some((start, _)) => (start.min(current), start.max(current))
+        };
+        self.selection = Some((start, stop)).filter(|(start, stop)| start != stop);
+    }
+
+    fn action_copy(&mut self) -> anyhow::Result<()> {
+        if let Some((start, stop)) = self.selection {
+            let text = self.cursor.get_substring(start, stop);
+            clipboard(&mut self.clipboard)?.set_text(text)?;
+        }
+        Ok(())
+    }
+
+    fn action_cut(&mut self) -> anyhow::Result<()> {
+        if self.selection.is_some() {
+            self.action_copy()?;
+            self.clear_selection();
+        }
+        Ok(())
+    }
+
+    fn action_paste(&mut self) -> anyhow::Result<()> {
+        let text = clipboard(&mut self.clipboard)?.get_text()?;
+        for c in text.chars() {
+            self.action_typing(c);
+        }
+        Ok(())
+    }
+}
+
+impl<T: CursorToCharIndexMapper> Default for MaskedTypingBehaviour<T> {
+    fn default() -> Self {
+        Self::with_clipboard(InputMask::default(), None)
+    }
+}
+
+impl<T: CursorToCharIndexMapper> TypingBehaviour for MaskedTypingBehaviour<T> {
+    fn handle_user_action(&mut self, action: UserAction) -> anyhow::Result<()> {
+        match action {
+            UserAction::ToggleInsert => (),
+            UserAction::Typing(c) => self.action_typing(c),
+            UserAction::Remove => self.action_backspace(),
+            UserAction::Delete => self.action_delete(),
+            UserAction::Cut => return self.action_cut(),
+            UserAction::Paste => return self.action_paste(),
+            UserAction::Copy => return self.action_copy(),
+            UserAction::CursorLeft => self.action_cursor_left(false),
+            UserAction::CursorRight => self.action_cursor_right(false),
+            UserAction::CursorLeftSelect => self.action_cursor_left(true),
+            UserAction::CursorRightSelect => self.action_cursor_right(true),
+            UserAction::ToStart => self.action_to_start(false),
+            UserAction::ToStartSelect => self.action_to_start(true),
+            UserAction::ToEnd => self.action_to_end(false),
+            UserAction::ToEndSelect => self.action_to_end(true),
+            UserAction::SelectAll => self.action_select_all(),
+            UserAction::Null => ()
+        }
+        Ok(())
+    }
+
+    fn get_visible_text(&mut self, width: usize) -> (String, Option<(usize, usize)>) {
+        let (text, _, selection) = self.cursor.get_visible_text(width, self.selection);
+        (text, selection)
+    }
+
+    fn get_cursor_position(&mut self, width: usize) -> usize {
+        let (_, cursor, _) = self.cursor.get_visible_text(width, self.selection);
+        cursor
+    }
+
+    fn get_overflow(&mut self, width: usize) -> (bool, bool) {
+        self.cursor.get_overflow(width)
+    }
+
+    fn get_text(&self) -> &str {
+        self.cursor.text()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.selection = None;
+        self.cursor = Cursor::new(self.mask.template());
+        self.filled = vec![false; self.mask.slots.len()];
+        self.cursor.set_position(self.next_slot(0));
+        for c in text.chars() {
+            self.action_typing(c);
+        }
+    }
+
+    fn typing_mode(&self) -> TypingMode {
+        TypingMode::Overwrite
+    }
+}
@@ -1,23 +1,46 @@
 use arboard::Clipboard;
+use crate::widgets::core::CursorShape;
 use super::cursor::{Cursor, CursorCoordinate, CursorToCharIndexMapper};
 use super::actions::UserAction;
 
 
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TypingMode {
+    #[default]
+    Insert,
+    Overwrite
+}
+
 pub trait TypingBehaviour: Default {
-    fn handle_user_action(&mut self, action: UserAction);
+    /// Fails if the clipboard can't be used for copy, cut or paste.
+    fn handle_user_action(&mut self, action: UserAction) -> anyhow::Result<()>;
     fn get_visible_text(&mut self, width: usize) -> (String, Option<(usize, usize)>);
     
     fn get_cursor_position(&mut self, width: usize) -> usize;
     
+    fn get_overflow(&mut self, width: usize) -> (bool, bool);
+    
     fn get_text(&self) -> &str;
+    
+    fn set_text(&mut self, text: String);
+    
+    fn typing_mode(&self) -> TypingMode;
+    
+    fn cursor_shape(&self) -> CursorShape {
+        match self.typing_mode() {
+            TypingMode::Insert => CursorShape::Bar,
+            TypingMode::Overwrite => CursorShape::Block
+        }
+    }
 }
 
 pub struct DefaultTypingBehaviour<T: CursorToCharIndexMapper> {
     cursor: Cursor<T>,
     insert_enabled: bool,
     selection: Option<(CursorCoordinate, CursorCoordinate)>,
-    clipboard: Clipboard
+    // Created when first used, since it can't be created without a display server
+    clipboard: Option<Clipboard>
 }
 
 impl<T: CursorToCharIndexMapper> DefaultTypingBehaviour<T> {
@@ -26,7 +49,7 @@ impl<T: CursorToCharIndexMapper> DefaultTypingBehaviour<T> {
             cursor: Cursor::new(text),
             insert_enabled: false,
             selection: None,
-            clipboard
+            clipboard: Some(clipboard)
         }
     }
     
@@ -185,52 +208,62 @@ impl<T: CursorToCharIndexMapper> DefaultTypingBehaviour<T> {
         }
     }
     
-    fn action_cut(&mut self) {
+    fn action_cut(&mut self) -> anyhow::Result<()> {
         if self.selection.is_some() {
-            self.action_copy();
+            self.action_copy()?;
             self.action_backspace();
         }
+        Ok(())
     }
     
-    fn action_copy(&mut self) {
+    fn action_copy(&mut self) -> anyhow::Result<()> {
         if let Some((start, stop)) = self.selection {
-            self.clipboard
-                .set_text(self.cursor.get_substring(start, stop))
-                .unwrap();
+            let text = self.cursor.get_substring(start, stop);
+            clipboard(&mut self.clipboard)?.set_text(text)?;
         }
+        Ok(())
     }
     
-    fn action_paste(&mut self) {
-        match self.selection {
-            None => {
-                let text = self.clipboard.get_text().unwrap();  
-                let end = self.cursor.insert_string_at_cursor(text.as_str());
-                self.cursor.set_position(end);
-            }
-            Some(_) => {
-                self.action_backspace();
-                self.action_paste();
-            }
+    fn action_paste(&mut self) -> anyhow::Result<()> {
+        let text = clipboard(&mut self.clipboard)?.get_text()?;
+        if self.selection.is_some() {
+            self.action_backspace();
         }
+        let end = self.cursor.insert_string_at_cursor(text.as_str());
+        self.cursor.set_position(end);
+        Ok(())
     }
 }
 
 impl<T: CursorToCharIndexMapper> Default for DefaultTypingBehaviour<T> {
     fn default() -> Self {
-        Self::new(String::new(), Clipboard::new().expect("Failed to create clipboard"))
+        Self {
+            cursor: Cursor::new(String::new()),
+            insert_enabled: false,
+            selection: None,
+            clipboard: None
+        }
     }   
 }
 
+// Get the clipboard, creating it on first use
+pub(super) fn clipboard(clipboard: &mut Option<Clipboard>) -> anyhow::Result<&mut Clipboard> {
+    match clipboard {
+        Some(clipboard) => Ok(clipboard),
+        None => Ok(clipboard.insert(Clipboard::new()?))
+    }
+}
+
 impl<T: CursorToCharIndexMapper> TypingBehaviour for DefaultTypingBehaviour<T> {
-    fn handle_user_action(&mut self, action: UserAction) {
+    fn handle_user_action(&mut self, action: UserAction) -> anyhow::Result<()> {
         match action {
             UserAction::ToggleInsert => self.action_toggle_insert(),
             UserAction::Typing(c) => self.action_typing(c),
             UserAction::Remove => self.action_backspace(),
             UserAction::Delete => self.action_delete(),
-            UserAction::Cut => self.action_cut(),
-            UserAction::Paste => self.action_paste(),
-            UserAction::Copy => self.action_copy(),
+            UserAction::Cut => return self.action_cut(),
+            UserAction::Paste => return self.action_paste(),
+            UserAction::Copy => return self.action_copy(),
             UserAction::CursorLeft => self.action_cursor_left(),
             UserAction::CursorRight => self.action_cursor_right(),
             UserAction::CursorLeftSelect => self.action_cursor_left_select(),
@@ -242,6 +275,7 @@ impl<T: CursorToCharIndexMapper> TypingBehaviour for DefaultTypingBehaviour<T> {
             UserAction::SelectAll => self.action_select_all(),
             UserAction::Null => ()
         }
+        Ok(())
     }
 
     fn get_visible_text(&mut self, width: usize) -> (String, Option<(usize, usize)>) {
@@ -254,7 +288,25 @@ impl<T: CursorToCharIndexMapper> TypingBehaviour for DefaultTypingBehaviour<T> {
         cursor 
     }
 
+    fn get_overflow(&mut self, width: usize) -> (bool, bool) {
+        self.cursor.get_overflow(width)
+    }
+
     fn get_text(&self) -> &str {
         self.cursor.text()
     }
+
+    fn set_text(&mut self, text: String) {
+        self.maybe_clear_selection();
+        self.cursor = Cursor::new(text);
+        self.cursor.move_to_end();
+    }
+
+    fn typing_mode(&self) -> TypingMode {
+        if self.insert_enabled {
+            TypingMode::Overwrite
+        } else {
+            TypingMode::Insert
+        }
+    }
 }
\ No newline at end of file